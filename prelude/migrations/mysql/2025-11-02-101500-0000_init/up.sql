@@ -0,0 +1,18 @@
+-- Your SQL goes here
+
+CREATE TABLE types (
+id INTEGER NOT NULL PRIMARY KEY,
+name VARCHAR(32) NOT NULL
+) ;
+
+INSERT INTO types (id, name) VALUES
+(1, 'grass'),
+(2, 'poison'),
+(3, 'fire'),
+(4, 'flying'),
+(5, 'water') ;
+
+CREATE TABLE pokemons (
+name VARCHAR(64) NOT NULL PRIMARY KEY,
+type ENUM('grass', 'poison', 'fire', 'flying', 'water') NOT NULL
+) ;
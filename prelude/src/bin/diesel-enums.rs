@@ -0,0 +1,186 @@
+use std::{env, process::exit};
+
+use diesel::{
+  prelude::*,
+  query_builder::SqlQuery,
+  query_dsl::LoadQuery,
+  sql_types::{BigInt, Text},
+};
+use diesel_enums::codegen::{lookup_table_enum, pg_enum};
+use dotenvy::dotenv;
+
+const USAGE: &str = "Generates the definitions of mapped rust enums from a database, using the connection from `DATABASE_URL`.
+
+Usage:
+  diesel-enums [--type <name>]
+      Generates an enum for each custom postgres type (or only for the given one)
+
+  diesel-enums --table <name> [--column <name>] [--no-ids]
+      Generates an enum from the `id` and `name` columns of a lookup table
+
+Options:
+  --type <name>     The custom postgres type to generate
+  --table <name>    The lookup table to read
+  --column <name>   The column with the names of the variants [default: name]
+  --no-ids          Only uses a name mapping, without reading the `id` column
+  --conn <path>     The path to the test runner used in the generated enums [default: the runner for the database]";
+
+#[derive(QueryableByName)]
+struct PgEnumMember {
+  #[diesel(sql_type = Text)]
+  type_name: String,
+  #[diesel(sql_type = Text)]
+  label: String,
+}
+
+#[derive(QueryableByName)]
+struct LookupRow {
+  #[diesel(sql_type = BigInt)]
+  id: i64,
+  #[diesel(sql_type = Text)]
+  name: String,
+}
+
+#[derive(Default)]
+struct Args {
+  pg_type: Option<String>,
+  table: Option<String>,
+  column: Option<String>,
+  no_ids: bool,
+  conn: Option<String>,
+}
+
+fn fail(message: &str) -> ! {
+  eprintln!("❌ {message}\n\n{USAGE}");
+  exit(1)
+}
+
+fn parse_args() -> Args {
+  let mut args = Args::default();
+  let mut input = env::args().skip(1);
+
+  while let Some(arg) = input.next() {
+    let mut value = || {
+      input
+        .next()
+        .unwrap_or_else(|| fail(&format!("Missing value for `{arg}`")))
+    };
+
+    match arg.as_str() {
+      "--type" => args.pg_type = Some(value()),
+      "--table" => args.table = Some(value()),
+      "--column" => args.column = Some(value()),
+      "--conn" => args.conn = Some(value()),
+      "--no-ids" => args.no_ids = true,
+      "-h" | "--help" => {
+        println!("{USAGE}");
+        exit(0)
+      }
+      _ => fail(&format!("Unknown argument `{arg}`")),
+    };
+  }
+
+  if args.table.is_none() && (args.column.is_some() || args.no_ids) {
+    fail("`--column` and `--no-ids` can only be used with `--table`");
+  }
+
+  if args.table.is_some() && args.pg_type.is_some() {
+    fail("`--type` cannot be used together with `--table`");
+  }
+
+  args
+}
+
+fn load_lookup_table<C>(conn: &mut C, args: &Args) -> Vec<(i64, String)>
+where
+  C: Connection,
+  for<'query> SqlQuery: LoadQuery<'query, C, LookupRow>,
+{
+  let table = args.table.as_deref().unwrap_or_default();
+  let column = args.column.as_deref().unwrap_or("name");
+
+  let id = if args.no_ids { "0" } else { "id" };
+
+  let rows: Vec<LookupRow> = diesel::sql_query(format!(
+    "SELECT CAST({id} AS BIGINT) AS id, {column} AS name FROM {table}"
+  ))
+  .load(conn)
+  .unwrap_or_else(|e| fail(&format!("Failed to load the rows of `{table}.{column}`: {e}")));
+
+  rows.into_iter().map(|row| (row.id, row.name)).collect()
+}
+
+fn lookup_enum(rows: &[(i64, String)], args: &Args, default_conn: &str) -> String {
+  lookup_table_enum(
+    args.table.as_deref().unwrap_or_default(),
+    args.column.as_deref().unwrap_or("name"),
+    rows,
+    !args.no_ids,
+    args.conn.as_deref().unwrap_or(default_conn),
+  )
+}
+
+fn pg_enums(conn: &mut PgConnection, args: &Args) -> Vec<String> {
+  let members: Vec<PgEnumMember> = diesel::sql_query(
+    "SELECT t.typname::text AS type_name, e.enumlabel::text AS label FROM pg_type t JOIN pg_enum e ON e.enumtypid = t.oid JOIN pg_namespace n ON n.oid = t.typnamespace WHERE n.nspname = current_schema() AND ($1::text IS NULL OR t.typname = $1::text) ORDER BY t.typname, e.enumsortorder",
+  )
+  .bind::<diesel::sql_types::Nullable<Text>, _>(args.pg_type.as_deref())
+  .load(conn)
+  .unwrap_or_else(|e| fail(&format!("Failed to load the custom postgres types: {e}")));
+
+  if members.is_empty() {
+    match &args.pg_type {
+      Some(pg_type) => fail(&format!("The custom type `{pg_type}` does not exist")),
+      None => fail("No custom enum types were found in the database"),
+    };
+  }
+
+  let conn_path = args.conn.as_deref().unwrap_or("diesel_enums::postgres_runner");
+
+  members
+    .chunk_by(|a, b| a.type_name == b.type_name)
+    .map(|chunk| {
+      let labels: Vec<String> = chunk.iter().map(|member| member.label.clone()).collect();
+
+      pg_enum(&chunk[0].type_name, &labels, conn_path)
+    })
+    .collect()
+}
+
+fn main() {
+  dotenv().ok();
+
+  let args = parse_args();
+
+  let database_url =
+    env::var("DATABASE_URL").unwrap_or_else(|_| fail("`DATABASE_URL` is not set"));
+
+  let is_postgres =
+    database_url.starts_with("postgres://") || database_url.starts_with("postgresql://");
+
+  let enums = if is_postgres {
+    let mut conn = PgConnection::establish(&database_url)
+      .unwrap_or_else(|e| fail(&format!("Failed to connect to the database: {e}")));
+
+    if args.table.is_some() {
+      let rows = load_lookup_table(&mut conn, &args);
+
+      vec![lookup_enum(&rows, &args, "diesel_enums::postgres_runner")]
+    } else {
+      pg_enums(&mut conn, &args)
+    }
+  } else {
+    let mut conn = SqliteConnection::establish(&database_url)
+      .unwrap_or_else(|e| fail(&format!("Failed to connect to the database: {e}")));
+
+    if args.table.is_none() {
+      fail("Custom types are only supported in postgres, use `--table` to read a lookup table");
+    }
+
+    let rows = load_lookup_table(&mut conn, &args);
+
+    vec![lookup_enum(&rows, &args, "diesel_enums::sqlite_runner")]
+  };
+
+  println!("{}", enums.join("\n"));
+}
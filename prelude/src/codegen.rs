@@ -0,0 +1,226 @@
+//! Generates the definitions of mapped rust enums from the values of a database source. It is used by the `diesel-enums` binary.
+
+use std::{collections::HashSet, fmt::Write};
+
+use convert_case::{Case, Casing};
+
+// The casings accepted by the `case` attribute, with the default one first so that it is preferred in case of ties
+const CASES: [(&str, Case); 7] = [
+  ("snake_case", Case::Snake),
+  ("PascalCase", Case::Pascal),
+  ("camelCase", Case::Camel),
+  ("UPPER_SNAKE", Case::UpperSnake),
+  ("kebab-case", Case::Kebab),
+  ("lowercase", Case::Lower),
+  ("UPPERCASE", Case::Upper),
+];
+
+struct Variant<'a> {
+  ident: String,
+  db_name: &'a str,
+  id: Option<i64>,
+}
+
+// Creates a valid and unique PascalCase identifier for a database value
+fn variant_ident(name: &str, used_idents: &mut HashSet<String>) -> String {
+  let mut ident: String = name
+    .to_case(Case::Pascal)
+    .chars()
+    .filter(|c| c.is_alphanumeric() || *c == '_')
+    .collect();
+
+  if !ident.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+    ident.insert(0, 'V');
+  }
+
+  if ident == "Self" {
+    ident.push('_');
+  }
+
+  let base = ident.clone();
+  let mut suffix = 2;
+
+  while !used_idents.insert(ident.clone()) {
+    ident = format!("{base}{suffix}");
+    suffix += 1;
+  }
+
+  ident
+}
+
+fn create_variants<'a>(rows: impl IntoIterator<Item = (Option<i64>, &'a str)>) -> Vec<Variant<'a>> {
+  let mut used_idents: HashSet<String> = HashSet::new();
+
+  rows
+    .into_iter()
+    .map(|(id, db_name)| Variant {
+      ident: variant_ident(db_name, &mut used_idents),
+      db_name,
+      id,
+    })
+    .collect()
+}
+
+// Picks the casing that matches the largest number of values, so that only the remaining ones need a `name` override
+fn infer_case(variants: &[Variant]) -> (&'static str, Case<'static>) {
+  CASES
+    .iter()
+    .rev()
+    .max_by_key(|(_, case)| {
+      variants
+        .iter()
+        .filter(|variant| variant.ident.to_case(*case) == variant.db_name)
+        .count()
+    })
+    .copied()
+    .unwrap_or(CASES[0])
+}
+
+// Returns the ranges of ids that must be skipped so that the automatic numbering matches the given ids, which must be sorted
+fn infer_skipped_ids(ids: &[i64]) -> Vec<(i64, i64)> {
+  let mut skipped: Vec<(i64, i64)> = Vec::new();
+  let mut current_id = 1;
+
+  for &id in ids.iter().filter(|id| **id >= 1) {
+    if id > current_id {
+      skipped.push((current_id, id));
+    }
+
+    current_id = id + 1;
+  }
+
+  skipped
+}
+
+fn format_enum(enum_name: &str, attributes: &[String], variants: &[Variant], case: Case) -> String {
+  let mut output = String::new();
+
+  writeln!(output, "#[diesel_enum({})]", attributes.join(", ")).unwrap();
+  writeln!(output, "pub enum {enum_name} {{").unwrap();
+
+  for variant in variants {
+    let mut overrides: Vec<String> = Vec::new();
+
+    if variant.ident.to_case(case) != variant.db_name {
+      overrides.push(format!("name = {:?}", variant.db_name));
+    }
+
+    // Ids below 1 cannot be reached with the automatic numbering
+    if let Some(id) = variant.id
+      && id < 1
+    {
+      overrides.push(format!("id = {id}"));
+    }
+
+    if !overrides.is_empty() {
+      writeln!(output, "  #[db_mapping({})]", overrides.join(", ")).unwrap();
+    }
+
+    writeln!(output, "  {},", variant.ident).unwrap();
+  }
+
+  output.push_str("}\n");
+
+  output
+}
+
+/// Generates a rust enum that is mapped to a custom postgres type, with the members listed in the same order as in the database.
+///
+/// `conn` is the path to the test runner used for the consistency checks.
+pub fn pg_enum(type_name: &str, members: &[String], conn: &str) -> String {
+  let variants = create_variants(members.iter().map(|member| (None, member.as_str())));
+
+  // Named like the type itself, since a generated plural (i.e. `Statuss`) would often be wrong
+  let enum_name = type_name.to_case(Case::Pascal);
+
+  let mut name_mapping = format!("path = crate::schema::sql_types::{enum_name}");
+
+  if enum_name.to_case(Case::Snake) != type_name {
+    name_mapping = format!("name = {type_name:?}, {name_mapping}");
+  }
+
+  let mut attributes = vec![format!("conn = {conn}"), format!("name_mapping({name_mapping})")];
+
+  let (case_name, case) = infer_case(&variants);
+
+  if case_name != CASES[0].0 {
+    attributes.push(format!("case = {case_name:?}"));
+  }
+
+  format_enum(&enum_name, &attributes, &variants, case)
+}
+
+/// Generates a rust enum that is mapped to a lookup table, with the given `(id, name)` rows.
+///
+/// The variants are sorted by id, and the gaps between the ids are listed in `skip_ids`. If `ids` is false, only a name mapping is used and the rows are kept in their original order.
+///
+/// `conn` is the path to the test runner used for the consistency checks.
+pub fn lookup_table_enum(
+  table_name: &str,
+  column: &str,
+  rows: &[(i64, String)],
+  ids: bool,
+  conn: &str,
+) -> String {
+  let mut rows: Vec<(Option<i64>, &str)> = rows
+    .iter()
+    .map(|(id, name)| (ids.then_some(*id), name.as_str()))
+    .collect();
+
+  rows.sort_by_key(|(id, _)| *id);
+
+  let variants = create_variants(rows);
+
+  let enum_name = table_name.to_case(Case::Pascal);
+
+  let mut attributes = vec![format!("conn = {conn}")];
+
+  if enum_name.to_case(Case::Snake) != table_name {
+    attributes.push(format!("table_name = {table_name:?}"));
+  }
+
+  if column != "name" {
+    attributes.push(format!("column = {column:?}"));
+  }
+
+  let (case_name, case) = infer_case(&variants);
+
+  if case_name != CASES[0].0 {
+    attributes.push(format!("case = {case_name:?}"));
+  }
+
+  attributes.push("name_mapping(default)".to_string());
+
+  if ids {
+    let ids: Vec<i64> = variants.iter().filter_map(|variant| variant.id).collect();
+
+    let needs_big_int = ids
+      .iter()
+      .any(|id| i32::try_from(*id).is_err());
+
+    if needs_big_int {
+      attributes.push("id_mapping(sql_type = diesel::sql_types::BigInt)".to_string());
+    } else {
+      attributes.push("id_mapping(default)".to_string());
+    }
+
+    let skipped = infer_skipped_ids(&ids);
+
+    if !skipped.is_empty() {
+      let ranges: Vec<String> = skipped
+        .iter()
+        .map(|(start, end)| {
+          if end - start == 1 {
+            start.to_string()
+          } else {
+            format!("{start}..{end}")
+          }
+        })
+        .collect();
+
+      attributes.push(format!("skip_ids({})", ranges.join(", ")));
+    }
+  }
+
+  format_enum(&enum_name, &attributes, &variants, case)
+}
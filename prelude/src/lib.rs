@@ -43,26 +43,121 @@
 
 pub use diesel_enums_proc_macro::*;
 
+#[cfg(feature = "cli")]
+pub mod codegen;
+
+#[cfg(feature = "snapshots")]
+pub mod snapshots;
+
+mod registry;
+
+pub use registry::*;
+
 #[cfg(feature = "test-utils")]
 mod test_runners;
 
 #[cfg(feature = "test-utils")]
 pub use test_runners::*;
 //
+use std::fmt::Display;
+
 use thiserror::Error;
 
+/// The error returned when a value cannot be converted into any of the variants of a mapped enum.
+///
+/// It is used by `from_db_name`, the generated [`TryFrom`] implementations for id mappings, as well as the `FromSql` implementations, where it can be recovered by downcasting the boxed error.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Error)]
+#[error("No `{enum_name}` variant matches the {value}")]
+pub struct UnknownVariant {
+  pub enum_name: &'static str,
+  pub value: UnknownValue,
+}
+
+/// The error returned when trying to write the fallback variant of a mapped enum (marked with `#[db_mapping(other)]`) to the database, since it has no corresponding value.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Error)]
+#[error("`{enum_name}::{variant}` is a fallback variant and has no value in the database")]
+pub struct FallbackVariantError {
+  pub enum_name: &'static str,
+  pub variant: &'static str,
+}
+
+/// The value that could not be matched to any variant of a mapped enum.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UnknownValue {
+  Name(String),
+  Id(i128),
+  Code(String),
+}
+
+impl Display for UnknownValue {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Name(name) => write!(f, "name `{name}`"),
+      Self::Id(id) => write!(f, "id {id}"),
+      Self::Code(code) => write!(f, "code `{code}`"),
+    }
+  }
+}
+
+/// A variant whose id in the database does not match the one defined in the rust enum.
+///
+/// Like the other mismatches, it refers to the variant with its name in the database, `expected` is the value defined in the rust enum and `found` is the value in the database.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IdMismatch {
+  /// The name of the variant in the database.
+  pub variant: String,
+  /// The id of the variant in the rust enum.
+  pub expected: i128,
+  /// The id of the variant in the database.
+  pub found: i128,
+}
+
 /// The kinds of errors that can occur when checking if a rust enum matches a database enum or table.
+///
+/// The variants are always referred to with their names in the database. In the mismatches, `expected` is what is defined in the rust enum and `found` is what is in the database.
+///
+/// With the `serde` feature, it is serialized with the name of the kind in snake case as `kind`, and its data as `details`.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "kind", content = "details", rename_all = "snake_case")
+)]
 pub enum ErrorKind {
   MissingFromDb(Vec<String>),
   MissingFromRustEnum(Vec<String>),
-  IdMismatches(Vec<(String, i64, i64)>),
+  IdMismatches(Vec<IdMismatch>),
+  /// The variants shared by the rust enum and the database enum are defined in a different order. Only checked when `check_order` is used.
+  OrderMismatch {
+    /// The order of the variants in the rust enum.
+    expected: Vec<String>,
+    /// The order of the variants in the database.
+    found: Vec<String>,
+  },
+  /// The value of one of the `extra_columns` (or of the code column used by `code_mapping`) in the database does not match the one defined for the variant.
+  ValueMismatch {
+    /// The name of the variant in the database.
+    variant: String,
+    column: String,
+    /// The value defined in the rust enum, formatted with `Debug`.
+    expected: String,
+    /// The value in the database, formatted with `Debug`.
+    found: String,
+  },
+  /// The runner could not connect to the database, with the reason of the failure.
+  ConnectionFailed(String),
+  /// The database source (the custom type, table or column) does not exist.
+  SourceNotFound(String),
+  /// The query used to load the values of the database source has failed. For the offline checks, `sql` contains the path of the snapshot instead.
+  QueryFailed { sql: String, message: String },
 }
 
 /// An error that is produced when a rust enum does not match a database enum or table.
 ///
 /// It includes the list of errors that may occur simultaneously, such as id mismatches as well as missing variants.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Error)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DbEnumError {
   pub rust_enum: String,
   pub db_source: DbEnumSource,
@@ -78,12 +173,147 @@ impl DbEnumError {
       errors: Vec::new(),
     }
   }
+
+  /// Creates an error for a runner that could not connect to the database.
+  ///
+  /// Since the runner does not know which enum is being checked, the rust enum and the database source are left empty, and they are filled in by the generated `check_consistency` method with [`with_enum`](Self::with_enum).
+  pub fn connection_failed(message: impl Into<String>) -> Self {
+    Self::without_enum(ErrorKind::ConnectionFailed(message.into()))
+  }
+
+  // An error whose rust enum and database source are filled in later with `with_enum`
+  pub(crate) fn without_enum(kind: ErrorKind) -> Self {
+    Self {
+      rust_enum: String::new(),
+      db_source: DbEnumSource::Column {
+        table: String::new(),
+        column: String::new(),
+      },
+      errors: vec![kind],
+    }
+  }
+
+  /// Creates a [`QueryFailed`](ErrorKind::QueryFailed) error, for a query that failed while loading the values of the database source.
+  pub fn query_failed(
+    rust_enum: &str,
+    db_source: DbEnumSource,
+    sql: &str,
+    error: impl Display,
+  ) -> Self {
+    Self {
+      rust_enum: rust_enum.to_string(),
+      db_source,
+      errors: vec![ErrorKind::QueryFailed {
+        sql: sql.to_string(),
+        message: error.to_string(),
+      }],
+    }
+  }
+
+  /// Creates a [`SourceNotFound`](ErrorKind::SourceNotFound) error, for a custom type, table or column that does not exist.
+  pub fn source_not_found(rust_enum: &str, db_source: DbEnumSource, message: impl Into<String>) -> Self {
+    Self {
+      rust_enum: rust_enum.to_string(),
+      db_source,
+      errors: vec![ErrorKind::SourceNotFound(message.into())],
+    }
+  }
+
+  /// Sets the rust enum and the database source of an error that was created without them (i.e. with [`connection_failed`](Self::connection_failed), or by the functions in `snapshots`).
+  pub fn with_enum(mut self, rust_enum: &str, db_source: DbEnumSource) -> Self {
+    if self.rust_enum.is_empty() {
+      self.rust_enum = rust_enum.to_string();
+      self.db_source = db_source;
+    }
+
+    self
+  }
+}
+
+/// The warning returned by a consistency check that passed, when the database contains values that are not mapped by the rust enum and are read as its fallback variant (marked with `#[db_mapping(other)]`).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnmappedValues {
+  pub rust_enum: String,
+  pub db_source: DbEnumSource,
+  /// The name of the fallback variant.
+  pub fallback: String,
+  /// The values that only exist in the database, sorted by name.
+  pub values: Vec<String>,
+}
+
+/// Determines which changes are applied to a lookup table by the generated `sync_to_db` method.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SyncPolicy {
+  /// Only inserts the variants that are missing from the table.
+  #[default]
+  InsertOnly,
+  /// Inserts the missing variants, deletes the rows that do not belong to the rust enum, replaces the rows with a mismatched id or code and updates the values of the extra columns.
+  ///
+  /// The rows with a mismatched id or code are deleted and inserted again, so that swapping the keys of two variants does not cause a conflict, which fails if they are referenced by a foreign key.
+  Full,
+}
+
+/// The changes applied to a lookup table by the generated `sync_to_db` method.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SyncReport {
+  /// The names of the variants that were inserted.
+  pub inserted: Vec<String>,
+  /// The names of the rows that were deleted.
+  pub deleted: Vec<String>,
+  /// The variants whose id was updated.
+  pub updated_ids: Vec<UpdatedId>,
+  /// The variants whose code was updated.
+  pub updated_codes: Vec<UpdatedCode>,
+  /// The names of the variants whose extra values were updated.
+  pub updated_values: Vec<String>,
+}
+
+/// A variant whose id was updated by the generated `sync_to_db` method.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UpdatedId {
+  /// The name of the variant in the database.
+  pub name: String,
+  /// The id that the row had in the database.
+  pub old_id: i128,
+  /// The id of the variant in the rust enum, which the row now has.
+  pub new_id: i128,
+}
+
+/// A variant whose code was updated by the generated `sync_to_db` method.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UpdatedCode {
+  /// The name of the variant in the database.
+  pub name: String,
+  /// The code that the row had in the database.
+  pub old_code: String,
+  /// The code of the variant in the rust enum, which the row now has.
+  pub new_code: String,
+}
+
+impl SyncReport {
+  /// Returns `true` if no changes were made.
+  pub fn is_empty(&self) -> bool {
+    self.inserted.is_empty()
+      && self.deleted.is_empty()
+      && self.updated_ids.is_empty()
+      && self.updated_codes.is_empty()
+      && self.updated_values.is_empty()
+  }
 }
 
-/// The database source for an enum mapping. It can be the name of a custom type (for postgres) or a regular column in other databases.
+/// The database source for an enum mapping. It can be the name of a custom type (for postgres), a native `ENUM` column (for mysql) or a regular column in other databases.
+///
+/// With the `serde` feature, it is serialized in the same way as [`ErrorKind`].
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "kind", content = "details", rename_all = "snake_case")
+)]
 pub enum DbEnumSource {
   CustomEnum(String),
+  MysqlEnum { table: String, column: String },
   Column { table: String, column: String },
 }
 
@@ -92,26 +322,112 @@ impl DbEnumSource {
   pub fn name(&self) -> String {
     match self {
       Self::CustomEnum(name) => name.clone(),
-      Self::Column { table, column } => format!("{table}.{column}"),
+      Self::MysqlEnum { table, column } | Self::Column { table, column } => {
+        format!("{table}.{column}")
+      }
     }
   }
 
-  /// The type of the target source (a postgres enum or regular column)
+  /// The type of the target source (a postgres enum, a mysql enum column or regular column)
   pub fn db_type(&self) -> &str {
     match self {
       Self::CustomEnum(_) => "enum",
+      Self::MysqlEnum { .. } => "enum column",
       Self::Column { .. } => "column",
     }
   }
 }
 
+/// Extracts the list of values from the definition of a MySQL `ENUM` column, as it is shown in `information_schema.COLUMNS.COLUMN_TYPE` (i.e. `enum('a','b','c')`).
+///
+/// The values are returned in the same order as they are defined in the column. Returns `None` if the definition is not a valid `ENUM` type.
+pub fn parse_mysql_enum(column_type: &str) -> Option<Vec<String>> {
+  let column_type = column_type.trim();
+
+  let (prefix, rest) = column_type.split_at_checked(5)?;
+
+  if !prefix.eq_ignore_ascii_case("enum(") {
+    return None;
+  }
+
+  let mut values: Vec<String> = Vec::new();
+  let mut chars = rest.chars().peekable();
+
+  loop {
+    match chars.next()? {
+      '\'' => {}
+      ')' if values.is_empty() => break,
+      _ => return None,
+    };
+
+    let mut value = String::new();
+
+    loop {
+      match chars.next()? {
+        '\'' if chars.peek() == Some(&'\'') => {
+          chars.next();
+          value.push('\'');
+        }
+        '\'' => break,
+        '\\' => value.push(chars.next()?),
+        c => value.push(c),
+      }
+    }
+
+    values.push(value);
+
+    match chars.next()? {
+      ',' => {}
+      ')' => break,
+      _ => return None,
+    }
+  }
+
+  if chars.next().is_some() {
+    return None;
+  }
+
+  Some(values)
+}
+
 #[cfg(feature = "pretty-test-errors")]
 mod pretty_errors {
   use std::fmt::Display;
 
   use owo_colors::OwoColorize;
 
-  use crate::{DbEnumError, ErrorKind};
+  use crate::{DbEnumError, DbEnumErrors, ErrorKind, IdMismatch, UnmappedValues};
+
+  impl Display for UnmappedValues {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      writeln!(
+        f,
+        "\n ⚠️ The values [ {} ] from the database {} `{}` are not mapped by the rust enum `{}` and will be read as `{}`",
+        self.values.join(", ").bright_red(),
+        self.db_source.db_type(),
+        self.db_source.name().bright_cyan(),
+        self.rust_enum.bright_yellow(),
+        self.fallback.bright_green()
+      )
+    }
+  }
+
+  impl Display for DbEnumErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      writeln!(
+        f,
+        "\n ❌ Found {} mapped enums that are out of sync with the database:",
+        self.errors.len().bright_red()
+      )
+      .unwrap();
+
+      for error in &self.errors {
+        write!(f, "{}", error).unwrap();
+      }
+
+      Ok(())
+    }
+  }
 
   impl Display for DbEnumError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -160,13 +476,53 @@ mod pretty_errors {
           Ok(())
         }
         ErrorKind::IdMismatches(items) => {
-          for (name, expected, found) in items {
-            writeln!(f, "\n  - Wrong id mapping for `{}`", name.bright_yellow()).unwrap();
+          for IdMismatch {
+            variant,
+            expected,
+            found,
+          } in items
+          {
+            writeln!(f, "\n  - Wrong id mapping for `{}`", variant.bright_yellow()).unwrap();
             writeln!(f, "    Expected: {}", expected.bright_green()).unwrap();
             writeln!(f, "    Found: {}", found.bright_red()).unwrap();
           }
           Ok(())
         }
+        ErrorKind::OrderMismatch { expected, found } => {
+          writeln!(f, "\n  - Variants are in the wrong order:").unwrap();
+          writeln!(f, "    Expected: [ {} ]", expected.join(", ").bright_green()).unwrap();
+          writeln!(f, "    Found: [ {} ]", found.join(", ").bright_red()).unwrap();
+          Ok(())
+        }
+        ErrorKind::ValueMismatch {
+          variant,
+          column,
+          expected,
+          found,
+        } => {
+          writeln!(
+            f,
+            "\n  - Wrong value in the column `{}` for `{}`",
+            column.bright_cyan(),
+            variant.bright_yellow()
+          )
+          .unwrap();
+          writeln!(f, "    Expected: {}", expected.bright_green()).unwrap();
+          writeln!(f, "    Found: {}", found.bright_red()).unwrap();
+          Ok(())
+        }
+        ErrorKind::ConnectionFailed(message) => {
+          writeln!(f, "\n  - Failed to connect to the {}:", "database".bright_cyan()).unwrap();
+          writeln!(f, "    {}", message.bright_red())
+        }
+        ErrorKind::SourceNotFound(message) => {
+          writeln!(f, "\n  - The {} source does not exist:", "database".bright_cyan()).unwrap();
+          writeln!(f, "    {}", message.bright_red())
+        }
+        ErrorKind::QueryFailed { sql, message } => {
+          writeln!(f, "\n  - Failed to run the query `{}`:", sql.bright_cyan()).unwrap();
+          writeln!(f, "    {}", message.bright_red())
+        }
       }
     }
   }
@@ -176,7 +532,38 @@ mod pretty_errors {
 mod standard_errors {
   use std::fmt::Display;
 
-  use crate::{DbEnumError, ErrorKind};
+  use crate::{DbEnumError, DbEnumErrors, ErrorKind, IdMismatch, UnmappedValues};
+
+  impl Display for UnmappedValues {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      writeln!(
+        f,
+        "\n ⚠️ The values [ {} ] from the database {} `{}` are not mapped by the rust enum `{}` and will be read as `{}`",
+        self.values.join(", "),
+        self.db_source.db_type(),
+        self.db_source.name(),
+        self.rust_enum,
+        self.fallback
+      )
+    }
+  }
+
+  impl Display for DbEnumErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      writeln!(
+        f,
+        "\n ❌ Found {} mapped enums that are out of sync with the database:",
+        self.errors.len()
+      )
+      .unwrap();
+
+      for error in &self.errors {
+        write!(f, "{}", error).unwrap();
+      }
+
+      Ok(())
+    }
+  }
 
   impl Display for DbEnumError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -215,15 +602,48 @@ mod standard_errors {
           )
         }
         ErrorKind::IdMismatches(items) => {
-          for (name, expected, found) in items {
+          for IdMismatch {
+            variant,
+            expected,
+            found,
+          } in items
+          {
             writeln!(
               f,
-              "\n  - Wrong id mapping for `{name}`. Expected: {expected}, found: {found}"
+              "\n  - Wrong id mapping for `{variant}`. Expected: {expected}, found: {found}"
             )
             .unwrap();
           }
           Ok(())
         }
+        ErrorKind::OrderMismatch { expected, found } => {
+          writeln!(
+            f,
+            "\n  - Variants are in the wrong order. Expected: [ {} ], found: [ {} ]",
+            expected.join(", "),
+            found.join(", ")
+          )
+        }
+        ErrorKind::ValueMismatch {
+          variant,
+          column,
+          expected,
+          found,
+        } => {
+          writeln!(
+            f,
+            "\n  - Wrong value in the column `{column}` for `{variant}`. Expected: {expected}, found: {found}"
+          )
+        }
+        ErrorKind::ConnectionFailed(message) => {
+          writeln!(f, "\n  - Failed to connect to the database: {message}")
+        }
+        ErrorKind::SourceNotFound(message) => {
+          writeln!(f, "\n  - The database source does not exist: {message}")
+        }
+        ErrorKind::QueryFailed { sql, message } => {
+          writeln!(f, "\n  - Failed to run the query `{sql}`: {message}")
+        }
       }
     }
   }
@@ -0,0 +1,62 @@
+use std::any::Any;
+
+use thiserror::Error;
+
+use crate::{DbEnumError, DbEnumSource, UnmappedValues};
+
+#[doc(hidden)]
+pub use inventory;
+
+/// The result of a consistency check, with the warning for the values that are read as the fallback variant if the check passes.
+pub type CheckResult = Result<Option<UnmappedValues>, DbEnumError>;
+
+/// A mapped enum that has been registered for [`check_all`]. Every enum that uses the `runtime_check` attribute is registered automatically.
+pub struct RegisteredEnum {
+  /// The name of the rust enum.
+  pub rust_enum: &'static str,
+  /// Returns the database source that the enum is mapped to.
+  pub db_source: fn() -> DbEnumSource,
+  /// Runs the consistency check of the enum. Returns `None` if the connection is not of the type set with `runtime_check`.
+  pub check: fn(&mut dyn Any) -> Option<CheckResult>,
+}
+
+inventory::collect!(RegisteredEnum);
+
+/// Returns an iterator over all the enums that have been registered with the `runtime_check` attribute, in any of the crates that are linked in the current binary.
+pub fn registered_enums() -> impl Iterator<Item = &'static RegisteredEnum> {
+  inventory::iter::<RegisteredEnum>.into_iter()
+}
+
+/// The errors returned by [`check_all`], one for each mapped enum that is out of sync with the database.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Error)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DbEnumErrors {
+  pub errors: Vec<DbEnumError>,
+}
+
+/// Runs the consistency checks for all the registered enums whose `runtime_check` uses the same connection type as `conn`, and collects all of their errors.
+///
+/// The other enums are skipped, so that enums that map to different databases can be checked with separate calls.
+///
+/// If all the checks pass, it returns the warnings for the enums whose fallback variant is used for some values of the database.
+pub fn check_all<C: 'static>(conn: &mut C) -> Result<Vec<UnmappedValues>, DbEnumErrors> {
+  let mut errors: Vec<DbEnumError> = Vec::new();
+  let mut warnings: Vec<UnmappedValues> = Vec::new();
+
+  for result in registered_enums().filter_map(|registered| (registered.check)(conn)) {
+    match result {
+      Ok(unmapped_values) => warnings.extend(unmapped_values),
+      Err(error) => errors.push(error),
+    }
+  }
+
+  if errors.is_empty() {
+    warnings.sort_by(|a, b| a.rust_enum.cmp(&b.rust_enum));
+
+    Ok(warnings)
+  } else {
+    errors.sort_by(|a, b| a.rust_enum.cmp(&b.rust_enum));
+
+    Err(DbEnumErrors { errors })
+  }
+}
@@ -0,0 +1,127 @@
+//! Snapshots of the values of a database source, which are used by the generated consistency checks when there is no database available (i.e. in CI).
+
+use std::{env, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{DbEnumError, ErrorKind};
+
+/// The values of a database source, in the same order as they are defined in the database.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnumSnapshot {
+  pub variants: Vec<SnapshotVariant>,
+}
+
+/// A value in a database source, with its id or code for the mappings that use them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotVariant {
+  pub name: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub id: Option<i128>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub code: Option<String>,
+}
+
+impl SnapshotVariant {
+  /// Creates a new variant with only a name.
+  pub fn new(name: String) -> Self {
+    Self {
+      name,
+      id: None,
+      code: None,
+    }
+  }
+
+  /// Returns the id of the variant, converted into the rust type of the id mapping.
+  ///
+  /// Returns a [`QueryFailed`](ErrorKind::QueryFailed) error if the variant has no id, or if it does not fit in the target type. The path of the snapshot is only used in the error.
+  pub fn id<T: TryFrom<i128>>(&self, path: impl AsRef<Path>) -> Result<T, DbEnumError> {
+    let id = self.id.ok_or_else(|| {
+      snapshot_error(
+        path.as_ref(),
+        format!("the variant `{}` has no id", self.name),
+      )
+    })?;
+
+    T::try_from(id).map_err(|_| {
+      snapshot_error(
+        path.as_ref(),
+        format!("the id {id} of the variant `{}` is out of range", self.name),
+      )
+    })
+  }
+
+  /// Returns the code of the variant.
+  ///
+  /// Returns a [`QueryFailed`](ErrorKind::QueryFailed) error if the variant has no code. The path of the snapshot is only used in the error.
+  pub fn code(&self, path: impl AsRef<Path>) -> Result<String, DbEnumError> {
+    self.code.clone().ok_or_else(|| {
+      snapshot_error(
+        path.as_ref(),
+        format!("the variant `{}` has no code", self.name),
+      )
+    })
+  }
+}
+
+// The errors of the snapshots are reported like a failed query, with the path of the snapshot in place of the SQL
+fn snapshot_error(path: &Path, message: String) -> DbEnumError {
+  DbEnumError::without_enum(ErrorKind::QueryFailed {
+    sql: path.display().to_string(),
+    message,
+  })
+}
+
+/// Returns `true` if the consistency checks should use the snapshots instead of connecting to the database, which happens when `DIESEL_ENUMS_OFFLINE` is set.
+pub fn is_offline() -> bool {
+  env::var_os("DIESEL_ENUMS_OFFLINE").is_some()
+}
+
+/// Returns `true` if `DIESEL_ENUMS_UPDATE_SNAPSHOTS` is set, in which case the consistency checks overwrite the snapshots with the values loaded from the database.
+pub fn should_update() -> bool {
+  env::var_os("DIESEL_ENUMS_UPDATE_SNAPSHOTS").is_some()
+}
+
+/// Reads a snapshot from a JSON file.
+///
+/// Returns a [`SourceNotFound`](ErrorKind::SourceNotFound) error if the file does not exist, and a [`QueryFailed`](ErrorKind::QueryFailed) error if it cannot be read or if it is not a valid snapshot.
+pub fn read_snapshot(path: impl AsRef<Path>) -> Result<EnumSnapshot, DbEnumError> {
+  let path = path.as_ref();
+
+  let content = fs::read_to_string(path).map_err(|e| {
+    if e.kind() == io::ErrorKind::NotFound {
+      DbEnumError::without_enum(ErrorKind::SourceNotFound(format!(
+        "the snapshot at `{}` does not exist. Run the consistency checks with a database and `DIESEL_ENUMS_UPDATE_SNAPSHOTS=1` to create it",
+        path.display()
+      )))
+    } else {
+      snapshot_error(path, format!("failed to read the snapshot: {e}"))
+    }
+  })?;
+
+  serde_json::from_str(&content)
+    .map_err(|e| snapshot_error(path, format!("invalid snapshot: {e}")))
+}
+
+/// Writes a snapshot to a JSON file, creating its parent directories if they are missing.
+///
+/// Returns a [`QueryFailed`](ErrorKind::QueryFailed) error if the file cannot be written.
+pub fn write_snapshot(path: impl AsRef<Path>, snapshot: &EnumSnapshot) -> Result<(), DbEnumError> {
+  let path = path.as_ref();
+
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|e| {
+      snapshot_error(
+        path,
+        format!("failed to create the directory of the snapshot: {e}"),
+      )
+    })?;
+  }
+
+  let mut content = serde_json::to_string_pretty(snapshot)
+    .map_err(|e| snapshot_error(path, format!("failed to serialize the snapshot: {e}")))?;
+  content.push('\n');
+
+  fs::write(path, content)
+    .map_err(|e| snapshot_error(path, format!("failed to write the snapshot: {e}")))
+}
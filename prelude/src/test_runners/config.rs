@@ -0,0 +1,276 @@
+use std::{any::Any, env, time::Duration};
+
+use deadpool_diesel::InteractError;
+use diesel::connection::SimpleConnection;
+#[cfg(feature = "migrations")]
+use diesel_migrations::{EmbeddedMigrations, MigrationHarness};
+use dotenvy::dotenv;
+use tokio::sync::OnceCell;
+
+use crate::DbEnumError;
+
+// A handle that deletes a temporary database when it is dropped
+pub(crate) type TempDatabase = Box<dyn Any + Send + Sync>;
+
+enum DatabaseUrl {
+  Env(String),
+  Url(String),
+  Temp,
+}
+
+/// The settings of the connection pool used by a test runner.
+///
+/// The default values are the ones used by the runners exported by this crate, namely a single connection to the database in `DATABASE_URL`, with a timeout of 5 seconds for getting or creating a connection and 2 seconds for recycling it.
+///
+/// ```rust,ignore
+/// let config = RunnerConfig::new()
+///   .database_url_env("USERS_DATABASE_URL")
+///   .max_size(4)
+///   .search_path("users");
+/// ```
+pub struct RunnerConfig {
+  database_url: DatabaseUrl,
+  pub(crate) max_size: usize,
+  pub(crate) wait_timeout: Option<Duration>,
+  pub(crate) create_timeout: Option<Duration>,
+  pub(crate) recycle_timeout: Option<Duration>,
+  setup_sql: Vec<String>,
+  search_path: Option<String>,
+  #[cfg(feature = "migrations")]
+  pub(crate) migrations: Option<EmbeddedMigrations>,
+}
+
+impl Default for RunnerConfig {
+  fn default() -> Self {
+    Self {
+      database_url: DatabaseUrl::Env("DATABASE_URL".to_string()),
+      max_size: 1,
+      wait_timeout: Some(Duration::from_secs(5)),
+      create_timeout: Some(Duration::from_secs(5)),
+      recycle_timeout: Some(Duration::from_secs(2)),
+      setup_sql: Vec::new(),
+      search_path: None,
+      #[cfg(feature = "migrations")]
+      migrations: None,
+    }
+  }
+}
+
+impl RunnerConfig {
+  /// Creates a new config with the default values.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Reads the url of the database from the given env variable (via regular env or `.env` file), instead of `DATABASE_URL`.
+  pub fn database_url_env(mut self, name: impl Into<String>) -> Self {
+    self.database_url = DatabaseUrl::Env(name.into());
+    self
+  }
+
+  /// Uses the given url for the database, instead of reading it from the env.
+  pub fn database_url(mut self, url: impl Into<String>) -> Self {
+    self.database_url = DatabaseUrl::Url(url.into());
+    self
+  }
+
+  /// Creates a new empty database the first time that the runner is used, instead of connecting to an existing one, so that the tests do not need any setup. It is usually combined with [`migrations`](Self::migrations) to create the schema.
+  ///
+  /// - For SQLite, the database is created in a new directory inside the temp directory.
+  /// - For Postgres, it requires the `pgtemp` feature, and a temporary server is started with [`pgtemp`](https://docs.rs/pgtemp) (which requires the postgres binaries such as `initdb` to be installed). If the server cannot be started, the runner returns a [`ConnectionFailed`](crate::ErrorKind::ConnectionFailed) error.
+  ///
+  /// The database (and the Postgres server) is owned by the [`RunnerPool`], and it is deleted when the pool is dropped. The pools of the runners are stored in statics, which are not dropped when the process exits, so a runner that is used until the end of the tests leaves its database behind.
+  pub fn temp_database(mut self) -> Self {
+    self.database_url = DatabaseUrl::Temp;
+    self
+  }
+
+  /// Applies the given migrations (created with `diesel_migrations::embed_migrations!`) when the pool is created, before running the first check.
+  #[cfg(feature = "migrations")]
+  pub fn migrations(mut self, migrations: EmbeddedMigrations) -> Self {
+    self.migrations = Some(migrations);
+    self
+  }
+
+  /// Sets the maximum number of connections in the pool.
+  pub fn max_size(mut self, max_size: usize) -> Self {
+    self.max_size = max_size;
+    self
+  }
+
+  /// Sets the timeout for getting a connection from the pool, or disables it with `None`.
+  pub fn wait_timeout(mut self, timeout: Option<Duration>) -> Self {
+    self.wait_timeout = timeout;
+    self
+  }
+
+  /// Sets the timeout for creating a new connection, or disables it with `None`.
+  pub fn create_timeout(mut self, timeout: Option<Duration>) -> Self {
+    self.create_timeout = timeout;
+    self
+  }
+
+  /// Sets the timeout for recycling a connection, or disables it with `None`.
+  pub fn recycle_timeout(mut self, timeout: Option<Duration>) -> Self {
+    self.recycle_timeout = timeout;
+    self
+  }
+
+  /// Adds some SQL to execute on each new connection, after the default setup of the runner (i.e. the PRAGMAs for SQLite). It can contain multiple statements.
+  pub fn setup_sql(mut self, sql: impl Into<String>) -> Self {
+    self.setup_sql.push(sql.into());
+    self
+  }
+
+  /// Sets the `search_path` of each new connection. Only used by the Postgres runners.
+  pub fn search_path(mut self, search_path: impl Into<String>) -> Self {
+    self.search_path = Some(search_path.into());
+    self
+  }
+
+  pub(crate) fn uses_temp_database(&self) -> bool {
+    matches!(self.database_url, DatabaseUrl::Temp)
+  }
+
+  pub(crate) fn resolve_database_url(&self, db_name: &str) -> Result<String, String> {
+    match &self.database_url {
+      DatabaseUrl::Url(url) => Ok(url.clone()),
+      DatabaseUrl::Temp => Err(format!("The {db_name} test runners cannot create temporary databases")),
+      DatabaseUrl::Env(var_name) => {
+        dotenv().ok();
+
+        env::var(var_name).map_err(|_| {
+          format!("Failed to set up testing pool for {db_name}: {var_name} is not set")
+        })
+      }
+    }
+  }
+
+  #[cfg(feature = "postgres")]
+  pub(crate) fn search_path_sql(&self) -> Option<String> {
+    self
+      .search_path
+      .as_ref()
+      .map(|search_path| format!("SET search_path TO {search_path};"))
+  }
+
+  // The SQL to execute on each new connection, with the default setup of the runner first
+  pub(crate) fn connection_setup_sql(&self, default_setup: impl IntoIterator<Item = String>) -> String {
+    default_setup
+      .into_iter()
+      .chain(self.setup_sql.iter().cloned())
+      .collect::<Vec<String>>()
+      .join("\n")
+  }
+}
+
+// Runs the setup SQL on a new connection of the pool
+pub(crate) async fn connection_setup<C>(
+  conn: &mut deadpool_diesel::Connection<C>,
+  setup_sql: String,
+) -> Result<(), String>
+where
+  C: SimpleConnection + Send + 'static,
+{
+  if setup_sql.is_empty() {
+    return Ok(());
+  }
+
+  conn
+    .interact(move |conn| conn.batch_execute(&setup_sql))
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("Failed to run the setup SQL of the test runner: {e}"))
+}
+
+// Applies the migrations of the config (if there are any) with a connection from a new pool
+#[cfg(feature = "migrations")]
+pub(crate) async fn run_migrations<C>(
+  conn: &deadpool_diesel::Connection<C>,
+  migrations: Option<EmbeddedMigrations>,
+  db_name: &str,
+) -> Result<(), String>
+where
+  C: diesel::Connection + MigrationHarness<C::Backend> + Send + 'static,
+{
+  let Some(migrations) = migrations else {
+    return Ok(());
+  };
+
+  conn
+    .interact(move |conn| {
+      conn
+        .run_pending_migrations(migrations)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("{db_name} testing pool thread crashed: {e}"))?
+    .map_err(|e| format!("Failed to run the migrations for the {db_name} test runner: {e}"))
+}
+
+// A panic inside of the callback is propagated as is, since it does not come from the runner
+pub(crate) fn interact_error(error: InteractError, db_name: &str) -> DbEnumError {
+  match error {
+    InteractError::Panic(payload) => std::panic::resume_unwind(payload),
+    InteractError::Aborted => {
+      DbEnumError::connection_failed(format!("The connection to the {db_name} database was aborted"))
+    }
+  }
+}
+
+/// A lazily initialized connection pool, which is used by the runners created with [`define_runner`](crate::define_runner).
+pub struct RunnerPool<P> {
+  // The pool, along with the handle of the temporary database that it uses (if any), so that the database is deleted when the pool is dropped
+  pub(crate) pool: OnceCell<(P, Option<TempDatabase>)>,
+}
+
+impl<P> RunnerPool<P> {
+  #[allow(clippy::new_without_default)]
+  pub const fn new() -> Self {
+    Self {
+      pool: OnceCell::const_new(),
+    }
+  }
+}
+
+/// Defines a test runner that can be used with the `conn` attribute, with a connection pool that uses the given [`RunnerConfig`].
+///
+/// The backend can be `postgres`, `sqlite` or `mysql`, and the respective feature must be enabled.
+///
+/// ```rust,ignore
+/// diesel_enums::define_runner! {
+///   pub users_runner: postgres = RunnerConfig::new().database_url_env("USERS_DATABASE_URL").max_size(4)
+/// }
+///
+/// #[diesel_enum(conn = users_runner, name_mapping(default))]
+/// enum UserRoles {
+///   Admin,
+///   Guest,
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_runner {
+  ($(#[$meta:meta])* $vis:vis $name:ident : postgres = $config:expr $(;)?) => {
+    $crate::define_runner!(@runner $(#[$meta])* $vis $name, $crate::__private::PgPool, $crate::__private::PgConnection, $config);
+  };
+
+  ($(#[$meta:meta])* $vis:vis $name:ident : sqlite = $config:expr $(;)?) => {
+    $crate::define_runner!(@runner $(#[$meta])* $vis $name, $crate::__private::SqlitePool, $crate::__private::SqliteConnection, $config);
+  };
+
+  ($(#[$meta:meta])* $vis:vis $name:ident : mysql = $config:expr $(;)?) => {
+    $crate::define_runner!(@runner $(#[$meta])* $vis $name, $crate::__private::MysqlPool, $crate::__private::MysqlConnection, $config);
+  };
+
+  (@runner $(#[$meta:meta])* $vis:vis $name:ident, $pool:ty, $conn:ty, $config:expr) => {
+    $(#[$meta])*
+    $vis async fn $name(
+      callback: impl FnOnce(&mut $conn) -> Result<(), $crate::DbEnumError> + std::marker::Send + 'static,
+    ) -> Result<(), $crate::DbEnumError> {
+      static POOL: $crate::RunnerPool<$pool> = $crate::RunnerPool::new();
+
+      POOL.run(|| $config, callback).await?
+    }
+  };
+}
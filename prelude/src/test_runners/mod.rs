@@ -1,3 +1,9 @@
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+mod config;
+
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+pub use config::{RunnerConfig, RunnerPool};
+
 #[cfg(feature = "sqlite")]
 mod sqlite;
 
@@ -9,3 +15,26 @@ mod postgres;
 
 #[cfg(feature = "postgres")]
 pub use postgres::postgres_runner;
+
+#[cfg(feature = "mysql")]
+mod mysql;
+
+#[cfg(feature = "mysql")]
+pub use mysql::mysql_runner;
+
+// The types used by `define_runner`
+#[doc(hidden)]
+pub mod __private {
+  #[cfg(feature = "mysql")]
+  pub use deadpool_diesel::mysql::Pool as MysqlPool;
+  #[cfg(feature = "postgres")]
+  pub use deadpool_diesel::postgres::Pool as PgPool;
+  #[cfg(feature = "sqlite")]
+  pub use deadpool_diesel::sqlite::Pool as SqlitePool;
+  #[cfg(feature = "mysql")]
+  pub use diesel::MysqlConnection;
+  #[cfg(feature = "postgres")]
+  pub use diesel::PgConnection;
+  #[cfg(feature = "sqlite")]
+  pub use diesel::SqliteConnection;
+}
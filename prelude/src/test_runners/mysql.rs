@@ -0,0 +1,91 @@
+use deadpool_diesel::{
+  mysql::{Hook, HookError, Manager as MysqlManager, Pool as MysqlPool},
+  Runtime,
+};
+use diesel::prelude::*;
+
+#[cfg(feature = "migrations")]
+use crate::test_runners::config::run_migrations;
+use crate::{
+  test_runners::config::{connection_setup, interact_error, RunnerConfig, RunnerPool},
+  DbEnumError,
+};
+
+static MYSQL_POOL: RunnerPool<MysqlPool> = RunnerPool::new();
+
+/// A test runner for MySQL (or MariaDB). It uses `deadpool-diesel` to create a connection pool that can be shared among tests, so that they can be executed faster.
+///
+/// It requires setting the env `DATABASE_URL` (via regular env or `.env` file) to set up the connection pool. Use [`define_runner`](crate::define_runner) to customize these settings.
+pub async fn mysql_runner(
+  callback: impl FnOnce(&mut MysqlConnection) -> Result<(), DbEnumError> + std::marker::Send + 'static,
+) -> Result<(), DbEnumError> {
+  MYSQL_POOL.run(RunnerConfig::default, callback).await?
+}
+
+impl RunnerPool<MysqlPool> {
+  /// Runs the callback with a connection from the pool, which is created with the given config when it is first used.
+  ///
+  /// If the pool cannot be created or a connection cannot be obtained, it returns a [`ConnectionFailed`](crate::ErrorKind::ConnectionFailed) error.
+  pub async fn run<T: Send + 'static>(
+    &self,
+    config: impl FnOnce() -> RunnerConfig,
+    callback: impl FnOnce(&mut MysqlConnection) -> T + std::marker::Send + 'static,
+  ) -> Result<T, DbEnumError> {
+    let (pool, _) = self
+      .pool
+      .get_or_try_init(|| async {
+        create_mysql_pool(config())
+          .await
+          .map(|pool| (pool, None))
+          .map_err(DbEnumError::connection_failed)
+      })
+      .await?;
+
+    let conn = pool.get().await.map_err(|e| {
+      DbEnumError::connection_failed(format!("Failed to get a connection to the MySQL database: {e}"))
+    })?;
+
+    conn
+      .interact(callback)
+      .await
+      .map_err(|e| interact_error(e, "MySQL"))
+  }
+}
+
+async fn create_mysql_pool(config: RunnerConfig) -> Result<MysqlPool, String> {
+  let database_url = config.resolve_database_url("MySQL")?;
+
+  let manager = MysqlManager::new(database_url, Runtime::Tokio1);
+
+  let setup_sql = config.connection_setup_sql([]);
+
+  let pool = MysqlPool::builder(manager)
+    .max_size(config.max_size)
+    .runtime(Runtime::Tokio1)
+    .wait_timeout(config.wait_timeout)
+    .create_timeout(config.create_timeout)
+    .recycle_timeout(config.recycle_timeout)
+    .post_create(Hook::async_fn(move |conn, _metrics| {
+      let setup_sql = setup_sql.clone();
+
+      Box::pin(async move {
+        connection_setup(conn, setup_sql)
+          .await
+          .map_err(HookError::message)
+      })
+    }))
+    .build()
+    .map_err(|e| format!("Failed to create the connection pool for MySQL: {e}"))?;
+
+  #[cfg(feature = "migrations")]
+  {
+    let conn = pool
+      .get()
+      .await
+      .map_err(|e| format!("Failed to get a connection to the MySQL database: {e}"))?;
+
+    run_migrations(&conn, config.migrations, "MySQL").await?;
+  }
+
+  Ok(pool)
+}
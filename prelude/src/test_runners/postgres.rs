@@ -1,49 +1,125 @@
-use std::{env, time::Duration};
-
 use deadpool_diesel::{
-  postgres::{Manager as PgManager, Pool as PgPool},
+  postgres::{Hook, HookError, Manager as PgManager, Pool as PgPool},
   Runtime,
 };
 use diesel::prelude::*;
-use dotenvy::dotenv;
-use tokio::sync::OnceCell;
+#[cfg(feature = "pgtemp")]
+use pgtemp::PgTempDB;
 
-use crate::DbEnumError;
+#[cfg(feature = "migrations")]
+use crate::test_runners::config::run_migrations;
+use crate::{
+  test_runners::config::{connection_setup, interact_error, RunnerConfig, RunnerPool, TempDatabase},
+  DbEnumError,
+};
 
-static POSTGRES_POOL: OnceCell<deadpool_diesel::postgres::Pool> = OnceCell::const_new();
+static POSTGRES_POOL: RunnerPool<PgPool> = RunnerPool::new();
 
 /// A test runner for Postgres. It uses `deadpool-diesel` to create a connection pool that can be shared among tests, so that they can be executed faster.
 ///
-/// It requires setting the env `DATABASE_URL` (via regular env or `.env` file) to set up the connection pool.
+/// It requires setting the env `DATABASE_URL` (via regular env or `.env` file) to set up the connection pool. Use [`define_runner`](crate::define_runner) to customize these settings.
 pub async fn postgres_runner(
   callback: impl FnOnce(&mut PgConnection) -> Result<(), DbEnumError> + std::marker::Send + 'static,
 ) -> Result<(), DbEnumError> {
-  POSTGRES_POOL
-    .get_or_init(|| async { create_pg_pool() })
-    .await
-    .get()
-    .await
-    .expect("Failed to get a connection to the Postgres database")
-    .interact(callback)
-    .await
-    .expect("Postgres testing pool thread crashed")
+  POSTGRES_POOL.run(RunnerConfig::default, callback).await?
 }
 
-#[track_caller]
-fn create_pg_pool() -> deadpool_diesel::postgres::Pool {
-  dotenv().ok();
+impl RunnerPool<PgPool> {
+  /// Runs the callback with a connection from the pool, which is created with the given config when it is first used.
+  ///
+  /// If the pool cannot be created or a connection cannot be obtained, it returns a [`ConnectionFailed`](crate::ErrorKind::ConnectionFailed) error.
+  pub async fn run<T: Send + 'static>(
+    &self,
+    config: impl FnOnce() -> RunnerConfig,
+    callback: impl FnOnce(&mut PgConnection) -> T + std::marker::Send + 'static,
+  ) -> Result<T, DbEnumError> {
+    let (pool, _) = self
+      .pool
+      .get_or_try_init(|| async {
+        create_pg_pool(config())
+          .await
+          .map_err(DbEnumError::connection_failed)
+      })
+      .await?;
+
+    let conn = pool.get().await.map_err(|e| {
+      DbEnumError::connection_failed(format!("Failed to get a connection to the Postgres database: {e}"))
+    })?;
+
+    conn
+      .interact(callback)
+      .await
+      .map_err(|e| interact_error(e, "Postgres"))
+  }
+}
 
-  let database_url = env::var("DATABASE_URL")
-    .expect("Failed to set up testing pool for Postgres: DATABASE_URL is not set");
+async fn create_pg_pool(config: RunnerConfig) -> Result<(PgPool, Option<TempDatabase>), String> {
+  let (database_url, temp_database) = if config.uses_temp_database() {
+    let (url, temp_database) = temp_database().await?;
+
+    (url, Some(temp_database))
+  } else {
+    (config.resolve_database_url("Postgres")?, None)
+  };
 
   let manager = PgManager::new(database_url, Runtime::Tokio1);
 
-  PgPool::builder(manager)
-    .max_size(1)
+  let setup_sql = config.connection_setup_sql(config.search_path_sql());
+
+  let pool = PgPool::builder(manager)
+    .max_size(config.max_size)
     .runtime(Runtime::Tokio1)
-    .wait_timeout(Some(Duration::from_secs(5)))
-    .create_timeout(Some(Duration::from_secs(5)))
-    .recycle_timeout(Some(Duration::from_secs(2)))
+    .wait_timeout(config.wait_timeout)
+    .create_timeout(config.create_timeout)
+    .recycle_timeout(config.recycle_timeout)
+    .post_create(Hook::async_fn(move |conn, _metrics| {
+      let setup_sql = setup_sql.clone();
+
+      Box::pin(async move {
+        connection_setup(conn, setup_sql)
+          .await
+          .map_err(HookError::message)
+      })
+    }))
     .build()
-    .expect("Failed to create the connection pool for Postgres")
+    .map_err(|e| format!("Failed to create the connection pool for Postgres: {e}"))?;
+
+  #[cfg(feature = "migrations")]
+  {
+    let conn = pool
+      .get()
+      .await
+      .map_err(|e| format!("Failed to get a connection to the Postgres database: {e}"))?;
+
+    run_migrations(&conn, config.migrations, "Postgres").await?;
+  }
+
+  Ok((pool, temp_database))
+}
+
+// Starts a temporary server, which is shut down when the handle is dropped together with the pool
+#[cfg(feature = "pgtemp")]
+async fn temp_database() -> Result<(String, TempDatabase), String> {
+  // `pgtemp` panics if the postgres binaries cannot be started
+  let db = tokio::task::spawn_blocking(PgTempDB::new).await.map_err(|e| {
+    let reason = e
+      .try_into_panic()
+      .ok()
+      .and_then(|panic| {
+        panic
+          .downcast_ref::<String>()
+          .cloned()
+          .or_else(|| panic.downcast_ref::<&str>().map(|message| message.to_string()))
+      })
+      .unwrap_or_else(|| "the task was cancelled".to_string());
+
+    format!("Failed to start a temporary Postgres server with `pgtemp`: {reason}")
+  })?;
+
+  Ok((db.connection_uri(), Box::new(db)))
+}
+
+#[cfg(not(feature = "pgtemp"))]
+async fn temp_database() -> Result<(String, TempDatabase), String> {
+  Err("The `pgtemp` feature must be enabled to create temporary Postgres databases".to_string())
 }
@@ -1,68 +1,119 @@
-use std::{env, time::Duration};
-
 use deadpool_diesel::{
   sqlite::{Hook, HookError, Manager as SqliteManager, Pool as SqlitePool},
   Runtime,
 };
-use deadpool_sync::SyncWrapper;
-use diesel::{prelude::*, SqliteConnection};
-use dotenvy::dotenv;
-use tokio::sync::OnceCell;
+use diesel::SqliteConnection;
+use tempfile::TempDir;
+
+#[cfg(feature = "migrations")]
+use crate::test_runners::config::run_migrations;
+use crate::{
+  test_runners::config::{connection_setup, interact_error, RunnerConfig, RunnerPool, TempDatabase},
+  DbEnumError,
+};
 
-use crate::DbEnumError;
+static SQLITE_POOL: RunnerPool<SqlitePool> = RunnerPool::new();
 
-static SQLITE_POOL: OnceCell<deadpool_diesel::sqlite::Pool> = OnceCell::const_new();
+// Sets the journal mode to WAL to allow for concurrent reads and faster tests
+const SQLITE_PRAGMAS: [&str; 5] = [
+  "PRAGMA synchronous = NORMAL;",
+  "PRAGMA busy_timeout = 2000;",
+  "PRAGMA journal_mode = WAL;",
+  "PRAGMA mmap_size = 134217728;",
+  "PRAGMA cache_size = 2000;",
+];
 
 /// A test runner for SQLite. It uses `deadpool-diesel` to create a connection pool, and sets the journal mode to WAL to allow for concurrent reads and faster tests.
 ///
-/// It requires setting the env `DATABASE_URL` (via regular env or `.env` file) to set up the connection pool.
+/// It requires setting the env `DATABASE_URL` (via regular env or `.env` file) to set up the connection pool. Use [`define_runner`](crate::define_runner) to customize these settings.
 pub async fn sqlite_runner(
   callback: impl FnOnce(&mut SqliteConnection) -> Result<(), DbEnumError> + std::marker::Send + 'static,
 ) -> Result<(), DbEnumError> {
-  SQLITE_POOL
-    .get_or_init(|| async { create_sqlite_pool() })
-    .await
-    .get()
-    .await
-    .expect("Failed to get a connection to the SQLite database")
-    .interact(callback)
-    .await
-    .expect("SQLite testing pool thread crashed")
+  SQLITE_POOL.run(RunnerConfig::default, callback).await?
+}
+
+impl RunnerPool<SqlitePool> {
+  /// Runs the callback with a connection from the pool, which is created with the given config when it is first used.
+  ///
+  /// If the pool cannot be created or a connection cannot be obtained, it returns a [`ConnectionFailed`](crate::ErrorKind::ConnectionFailed) error.
+  pub async fn run<T: Send + 'static>(
+    &self,
+    config: impl FnOnce() -> RunnerConfig,
+    callback: impl FnOnce(&mut SqliteConnection) -> T + std::marker::Send + 'static,
+  ) -> Result<T, DbEnumError> {
+    let (pool, _) = self
+      .pool
+      .get_or_try_init(|| async {
+        create_sqlite_pool(config())
+          .await
+          .map_err(DbEnumError::connection_failed)
+      })
+      .await?;
+
+    let conn = pool.get().await.map_err(|e| {
+      DbEnumError::connection_failed(format!("Failed to get a connection to the SQLite database: {e}"))
+    })?;
+
+    conn
+      .interact(callback)
+      .await
+      .map_err(|e| interact_error(e, "SQLite"))
+  }
 }
 
-#[track_caller]
-fn create_sqlite_pool() -> deadpool_diesel::sqlite::Pool {
-  dotenv().ok();
+async fn create_sqlite_pool(config: RunnerConfig) -> Result<(SqlitePool, Option<TempDatabase>), String> {
+  let (database_url, temp_database) = if config.uses_temp_database() {
+    let (url, temp_dir) = temp_database()?;
 
-  let database_url = env::var("DATABASE_URL")
-    .expect("Failed to set up testing pool for SQLite: DATABASE_URL is not set");
+    (url, Some(Box::new(temp_dir) as TempDatabase))
+  } else {
+    (config.resolve_database_url("SQLite")?, None)
+  };
 
   let manager = SqliteManager::new(database_url, Runtime::Tokio1);
 
-  SqlitePool::builder(manager)
-    .max_size(1)
+  let setup_sql = config.connection_setup_sql(SQLITE_PRAGMAS.map(String::from));
+
+  let pool = SqlitePool::builder(manager)
+    .max_size(config.max_size)
     .runtime(Runtime::Tokio1)
-    .wait_timeout(Some(Duration::from_secs(5)))
-    .create_timeout(Some(Duration::from_secs(5)))
-    .recycle_timeout(Some(Duration::from_secs(2)))
+    .wait_timeout(config.wait_timeout)
+    .create_timeout(config.create_timeout)
+    .recycle_timeout(config.recycle_timeout)
     .post_create(Hook::async_fn(move |conn, _metrics| {
-      Box::pin(connection_setup(conn))
+      let setup_sql = setup_sql.clone();
+
+      Box::pin(async move {
+        connection_setup(conn, setup_sql)
+          .await
+          .map_err(HookError::message)
+      })
     }))
     .build()
-    .expect("Failed to build the connection pool for SQLite")
+    .map_err(|e| format!("Failed to build the connection pool for SQLite: {e}"))?;
+
+  #[cfg(feature = "migrations")]
+  {
+    let conn = pool
+      .get()
+      .await
+      .map_err(|e| format!("Failed to get a connection to the SQLite database: {e}"))?;
+
+    run_migrations(&conn, config.migrations, "SQLite").await?;
+  }
+
+  Ok((pool, temp_database))
 }
 
-async fn connection_setup(conn: &mut SyncWrapper<SqliteConnection>) -> Result<(), HookError> {
-  let _ = conn
-    .interact(move |conn| {
-      diesel::sql_query("PRAGMA synchronous = NORMAL;").execute(conn)?;
-      diesel::sql_query("PRAGMA busy_timeout = 2000;").execute(conn)?;
-      diesel::sql_query("PRAGMA journal_mode = WAL;").execute(conn)?;
-      diesel::sql_query("PRAGMA mmap_size = 134217728;").execute(conn)?;
-      diesel::sql_query("PRAGMA cache_size = 2000;").execute(conn)?;
-      QueryResult::Ok(())
-    })
-    .await;
-
-  Ok(())
+// A new file in a temp directory, since each connection to `:memory:` would open a different database.
+// The directory is deleted when the handle is dropped together with the pool, along with the `-wal` and `-shm` files that sqlite creates next to the database.
+fn temp_database() -> Result<(String, TempDir), String> {
+  let temp_dir = tempfile::Builder::new()
+    .prefix("diesel_enums_")
+    .tempdir()
+    .map_err(|e| format!("Failed to create a temporary directory for the SQLite database: {e}"))?;
+
+  let url = temp_dir.path().join("db.sqlite3").to_string_lossy().into_owned();
+
+  Ok((url, temp_dir))
 }
@@ -0,0 +1,104 @@
+use diesel_enums::{
+  codegen::{lookup_table_enum, pg_enum},
+  diesel_enum,
+};
+
+#[test]
+fn lookup_table() {
+  let rows = [
+    (1, "fire".to_string()),
+    (2, "grass".to_string()),
+    (5, "water".to_string()),
+    (6, "it's".to_string()),
+    (8, "dark_matter".to_string()),
+    (0, "Unknown".to_string()),
+  ];
+
+  assert_eq!(
+    lookup_table_enum("pokemon_types", "name", &rows, true, "diesel_enums::sqlite_runner"),
+    r#"#[diesel_enum(conn = diesel_enums::sqlite_runner, name_mapping(default), id_mapping(default), skip_ids(3..5, 7))]
+pub enum PokemonTypes {
+  #[db_mapping(name = "Unknown", id = 0)]
+  Unknown,
+  Fire,
+  Grass,
+  Water,
+  #[db_mapping(name = "it's")]
+  Its,
+  DarkMatter,
+}
+"#
+  );
+}
+
+// The enum generated in the test above, without the connection and the name mapping
+#[diesel_enum(skip_check, id_mapping(default), skip_ids(3..5, 7))]
+pub enum PokemonTypes {
+  #[db_mapping(name = "Unknown", id = 0)]
+  Unknown,
+  Fire,
+  Grass,
+  Water,
+  #[db_mapping(name = "it's")]
+  Its,
+  DarkMatter,
+}
+
+#[test]
+fn generated_mapping() {
+  assert_eq!(PokemonTypes::IDS, [0, 1, 2, 5, 6, 8]);
+  assert_eq!(
+    PokemonTypes::DB_NAMES,
+    ["Unknown", "fire", "grass", "water", "it's", "dark_matter"]
+  );
+}
+
+#[test]
+fn lookup_table_options() {
+  let rows = [
+    (3_000_000_000, "Steel".to_string()),
+    (1, "Grass".to_string()),
+    (2, "Rock".to_string()),
+  ];
+
+  assert_eq!(
+    lookup_table_enum("types", "label", &rows, true, "crate::runner"),
+    r#"#[diesel_enum(conn = crate::runner, column = "label", case = "PascalCase", name_mapping(default), id_mapping(sql_type = diesel::sql_types::BigInt), skip_ids(3..3000000000))]
+pub enum Types {
+  Grass,
+  Rock,
+  Steel,
+}
+"#
+  );
+
+  assert_eq!(
+    lookup_table_enum("Types", "name", &rows, false, "crate::runner"),
+    r#"#[diesel_enum(conn = crate::runner, table_name = "Types", case = "PascalCase", name_mapping(default))]
+pub enum Types {
+  Steel,
+  Grass,
+  Rock,
+}
+"#
+  );
+}
+
+#[test]
+fn custom_type() {
+  let members = ["GRASS", "POISON", "fire", "1st"].map(String::from);
+
+  assert_eq!(
+    pg_enum("pokemon_type", &members, "diesel_enums::postgres_runner"),
+    r#"#[diesel_enum(conn = diesel_enums::postgres_runner, name_mapping(path = crate::schema::sql_types::PokemonType), case = "UPPER_SNAKE")]
+pub enum PokemonType {
+  Grass,
+  Poison,
+  #[db_mapping(name = "fire")]
+  Fire,
+  #[db_mapping(name = "1st")]
+  V1St,
+}
+"#
+  );
+}
@@ -0,0 +1,39 @@
+use diesel_enums::diesel_enum;
+
+#[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+#[diesel(postgres_type(name = "element"))]
+pub struct Element;
+
+#[diesel_enum(migrations = "tests/sql_migrations", check_order, name_mapping(path = Element))]
+pub enum Elements {
+  Fire,
+  Grass,
+  Water,
+  Thunder,
+}
+
+#[diesel_enum(migrations = "tests/sql_migrations", id_mapping(default), skip_ids(3..5))]
+pub enum Rarities {
+  Common,
+  Rare,
+  Legendary,
+  Mythical,
+}
+
+#[diesel_enum(migrations = "tests/sql_migrations", name_mapping(default), code_mapping(default))]
+pub enum Regions {
+  #[db_mapping(code = "KAN")]
+  Kanto,
+  #[db_mapping(code = "JOH")]
+  Johto,
+  #[db_mapping(name = "hoenn's", code = "HOE")]
+  Hoenn,
+}
+
+#[test]
+fn migrations_mapping() {
+  assert_eq!(Elements::DB_NAMES, ["fire", "grass", "water", "thunder"]);
+  assert_eq!(Rarities::IDS, [1, 2, 5, 6]);
+  assert_eq!(RegionsCode::CODES, ["KAN", "JOH", "HOE"]);
+  assert_eq!(RegionsCode::from(Regions::Hoenn), RegionsCode::Hoenn);
+}
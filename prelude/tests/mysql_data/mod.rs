@@ -0,0 +1,31 @@
+pub mod schema;
+
+use diesel::prelude::*;
+use diesel_enums::{mysql_runner, DbEnumError};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use tokio::sync::OnceCell;
+
+const MYSQL_MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations/mysql");
+
+static MIGRATIONS_RUN: OnceCell<()> = OnceCell::const_new();
+
+// Runs the migrations on the database from `DATABASE_URL` (i.e. the one from `compose.yaml`) before the first test, and then uses the shipped runner
+pub async fn mysql_testing_callback(
+  callback: impl FnOnce(&mut MysqlConnection) -> Result<(), DbEnumError> + std::marker::Send + 'static,
+) -> Result<(), DbEnumError> {
+  MIGRATIONS_RUN
+    .get_or_init(|| async {
+      mysql_runner(|conn| {
+        conn
+          .run_pending_migrations(MYSQL_MIGRATIONS)
+          .expect("Failed to run migrations");
+
+        Ok(())
+      })
+      .await
+      .unwrap();
+    })
+    .await;
+
+  mysql_runner(callback).await
+}
@@ -0,0 +1,22 @@
+use diesel_enums::parse_mysql_enum;
+
+#[test]
+fn parses_enum_values() {
+  let values = parse_mysql_enum("enum('grass','poison','fire')").unwrap();
+
+  assert_eq!(values, ["grass", "poison", "fire"]);
+}
+
+#[test]
+fn parses_escaped_values() {
+  let values = parse_mysql_enum(r"ENUM('it''s','comma,inside','back\\slash')").unwrap();
+
+  assert_eq!(values, ["it's", "comma,inside", r"back\slash"]);
+}
+
+#[test]
+fn rejects_other_types() {
+  assert_eq!(parse_mysql_enum("varchar(255)"), None);
+  assert_eq!(parse_mysql_enum("set('a','b')"), None);
+  assert_eq!(parse_mysql_enum("enum('a','b'"), None);
+}
@@ -0,0 +1,112 @@
+//! These tests require a MySQL (or MariaDB) database, such as the one defined in `compose.yaml`:
+//!
+//! `DATABASE_URL=mysql://root@127.0.0.1:3306/diesel_enums cargo test --features mysql --test mysql_tests`
+#![cfg(feature = "mysql")]
+
+mod mysql_data;
+
+use diesel::prelude::*;
+use diesel_enums::{diesel_enum, ErrorKind};
+use mysql_data::{mysql_testing_callback, schema::*};
+
+#[diesel_enum(conn = mysql_testing_callback, table = types, name_mapping(default), id_mapping(default))]
+pub enum Types {
+  Grass,
+  Poison,
+  Fire,
+  Flying,
+  Water,
+}
+
+#[diesel_enum(conn = mysql_testing_callback, check_order, table_name = "pokemons", column = "type", name_mapping(mysql_enum, path = sql_types::PokemonsTypeEnum))]
+pub enum PokemonTypes {
+  Grass,
+  Poison,
+  Fire,
+  Flying,
+  Water,
+}
+
+#[derive(Queryable, Selectable, Insertable, Debug, PartialEq)]
+#[diesel(table_name = pokemons)]
+struct Pokemon {
+  name: String,
+  type_: PokemonTypes,
+}
+
+mod missing_variant {
+  use super::*;
+
+  #[diesel_enum(conn = mysql_testing_callback, skip_test, table = types, name_mapping(default), id_mapping(default))]
+  pub enum Types {
+    Grass,
+    Poison,
+    Fire,
+    Flying,
+    Water,
+    Bug,
+  }
+
+  #[tokio::test]
+  async fn missing_variant() {
+    let errors = Types::check_consistency().await.unwrap_err().errors;
+
+    assert_eq!(errors, [ErrorKind::MissingFromDb(vec!["bug".to_string()])]);
+  }
+}
+
+mod wrong_order {
+  use super::*;
+
+  #[diesel_enum(conn = mysql_testing_callback, skip_test, check_order, table_name = "pokemons", column = "type", name_mapping(mysql_enum, path = sql_types::PokemonsTypeEnum))]
+  pub enum PokemonTypes {
+    Poison,
+    Grass,
+    Fire,
+    Flying,
+    Water,
+  }
+
+  #[tokio::test]
+  async fn wrong_order() {
+    let errors = PokemonTypes::check_consistency().await.unwrap_err().errors;
+
+    assert!(matches!(errors.as_slice(), [ErrorKind::OrderMismatch { .. }]));
+  }
+}
+
+#[tokio::test]
+async fn mysql_queries() {
+  mysql_testing_callback(|conn| {
+    conn.test_transaction(|conn| {
+      let bulbasaur = Pokemon {
+        name: "bulbasaur".to_string(),
+        type_: PokemonTypes::Grass,
+      };
+
+      diesel::insert_into(pokemons::table)
+        .values(&bulbasaur)
+        .execute(conn)?;
+
+      let pokemon: Pokemon = pokemons::table
+        .select(Pokemon::as_select())
+        .filter(pokemons::type_.eq(PokemonTypes::Grass))
+        .get_result(conn)?;
+
+      assert_eq!(pokemon, bulbasaur);
+
+      let type_id: i32 = types::table
+        .select(types::id)
+        .filter(types::name.eq(Types::Fire))
+        .get_result(conn)?;
+
+      assert_eq!(TypesId::try_from(type_id).unwrap(), TypesId::Fire);
+
+      QueryResult::Ok(())
+    });
+
+    Ok(())
+  })
+  .await
+  .unwrap();
+}
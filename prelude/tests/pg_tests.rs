@@ -1,7 +1,7 @@
 mod pg_data;
 
 use diesel_enums::{diesel_enum, ErrorKind};
-use pg_data::{models::*, postgres_testing_callback, schema::*};
+use pg_data::{models::*, postgres_testing_callback, run_pg_query, schema::*};
 
 #[tokio::test]
 async fn you_shall_pass() {
@@ -136,3 +136,183 @@ mod extra_variant {
     };
   }
 }
+
+mod correct_order {
+  use super::*;
+
+  #[diesel_enum(conn = postgres_testing_callback, check_order, name_mapping(name = "pokemon_type", path = sql_types::PokemonType))]
+  enum PokemonTypes {
+    Grass,
+    Poison,
+    Fire,
+    Flying,
+    Water,
+    Bug,
+    Normal,
+    Electric,
+    Ground,
+    Fairy,
+    Fighting,
+    Psychic,
+    Rock,
+    Steel,
+    Ice,
+    Ghost,
+    Dragon,
+    Dark,
+  }
+}
+
+mod wrong_order {
+  use super::*;
+
+  #[diesel_enum(conn = postgres_testing_callback, skip_test, check_order, name_mapping(name = "pokemon_type", path = sql_types::PokemonType))]
+  enum PokemonTypes {
+    // Swapped
+    Poison,
+    Grass,
+    Fire,
+    Flying,
+    Water,
+    Bug,
+    Normal,
+    Electric,
+    Ground,
+    Fairy,
+    Fighting,
+    Psychic,
+    Rock,
+    Steel,
+    Ice,
+    Ghost,
+    Dragon,
+    Dark,
+  }
+
+  #[tokio::test]
+  async fn wrong_order() {
+    let errors = PokemonTypes::check_consistency().await.unwrap_err().errors;
+
+    assert_eq!(errors.len(), 1);
+
+    if let ErrorKind::OrderMismatch { expected, found } = errors.first().unwrap() {
+      assert_eq!(expected[..2], ["poison", "grass"]);
+      assert_eq!(found[..2], ["grass", "poison"]);
+      assert_eq!(expected[2..], found[2..]);
+    } else {
+      panic!();
+    }
+  }
+}
+
+mod value_added_before {
+  use diesel::prelude::*;
+
+  use super::*;
+
+  #[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+  #[diesel(postgres_type(name = "rarity"))]
+  struct Rarity;
+
+  #[diesel_enum(conn = postgres_testing_callback, skip_test, check_order, name_mapping(path = Rarity))]
+  enum Rarities {
+    Common,
+    Rare,
+    Legendary,
+  }
+
+  #[tokio::test]
+  async fn value_added_before() {
+    run_pg_query(|conn| {
+      diesel::sql_query("CREATE TYPE rarity AS ENUM ('common', 'legendary')").execute(conn)?;
+      diesel::sql_query("ALTER TYPE rarity ADD VALUE 'rare' BEFORE 'common'").execute(conn)
+    })
+    .await
+    .unwrap();
+
+    let errors = Rarities::check_consistency().await.unwrap_err().errors;
+
+    assert_eq!(
+      errors,
+      [ErrorKind::OrderMismatch {
+        expected: vec!["common".to_string(), "rare".to_string(), "legendary".to_string()],
+        found: vec!["rare".to_string(), "common".to_string(), "legendary".to_string()],
+      }]
+    );
+  }
+}
+
+mod aliases {
+  use diesel::{dsl::sql, prelude::*};
+
+  use super::*;
+
+  #[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+  #[diesel(postgres_type(name = "element"))]
+  struct Element;
+
+  #[diesel_enum(conn = postgres_testing_callback, skip_test, check_order, name_mapping(path = Element))]
+  enum Elements {
+    Grass,
+    #[db_mapping(aliases("flame", "FIRE"))]
+    Fire,
+  }
+
+  #[tokio::test]
+  async fn aliases() {
+    let (renamed, written) = run_pg_query(|conn| {
+      diesel::sql_query("CREATE TYPE element AS ENUM ('grass', 'flame', 'fire')").execute(conn)?;
+
+      let renamed = diesel::select(sql::<Element>("'flame'::element")).get_result::<Elements>(conn)?;
+
+      let written = diesel::select(
+        sql::<diesel::sql_types::Text>("")
+          .bind::<Element, _>(Elements::Fire)
+          .sql("::text"),
+      )
+      .get_result::<String>(conn)?;
+
+      Ok((renamed, written))
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(renamed, Elements::Fire);
+    assert_eq!(written, "fire");
+    assert_eq!(Elements::from_db_name("FIRE").unwrap(), Elements::Fire);
+    assert_eq!(Elements::Fire.db_name(), "fire");
+
+    Elements::check_consistency().await.unwrap();
+  }
+}
+
+mod generated_sql {
+  use diesel::prelude::*;
+
+  use super::*;
+
+  #[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+  #[diesel(postgres_type(name = "generated_type"))]
+  struct GeneratedType;
+
+  #[diesel_enum(conn = postgres_testing_callback, skip_test, case = "UPPERCASE", name_mapping(path = GeneratedType))]
+  enum Generated {
+    First,
+    #[db_mapping(name = "it's")]
+    Second,
+  }
+
+  #[tokio::test]
+  async fn create_type_sql() {
+    assert_eq!(
+      Generated::create_type_sql(),
+      "CREATE TYPE generated_type AS ENUM ('FIRST', 'it''s');"
+    );
+
+    run_pg_query(|conn| diesel::sql_query(Generated::create_type_sql()).execute(conn))
+      .await
+      .unwrap();
+
+    Generated::check_consistency().await.unwrap();
+  }
+}
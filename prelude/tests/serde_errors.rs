@@ -0,0 +1,71 @@
+use diesel_enums::{DbEnumError, DbEnumErrors, DbEnumSource, ErrorKind, IdMismatch};
+use serde_json::json;
+
+fn drift_report() -> DbEnumError {
+  DbEnumError {
+    rust_enum: "Types".to_string(),
+    db_source: DbEnumSource::Column {
+      table: "types".to_string(),
+      column: "name".to_string(),
+    },
+    errors: vec![
+      ErrorKind::MissingFromDb(vec!["shadow".to_string()]),
+      ErrorKind::IdMismatches(vec![IdMismatch {
+        variant: "grass".to_string(),
+        expected: 1,
+        found: 20,
+      }]),
+      ErrorKind::QueryFailed {
+        sql: "SELECT id, name FROM types".to_string(),
+        message: "database is locked".to_string(),
+      },
+    ],
+  }
+}
+
+#[test]
+fn serialized_fields() {
+  let value = serde_json::to_value(drift_report()).unwrap();
+
+  assert_eq!(
+    value,
+    json!({
+      "rust_enum": "Types",
+      "db_source": {
+        "kind": "column",
+        "details": { "table": "types", "column": "name" }
+      },
+      "errors": [
+        { "kind": "missing_from_db", "details": ["shadow"] },
+        {
+          "kind": "id_mismatches",
+          "details": [{ "variant": "grass", "expected": 1, "found": 20 }]
+        },
+        {
+          "kind": "query_failed",
+          "details": { "sql": "SELECT id, name FROM types", "message": "database is locked" }
+        }
+      ]
+    })
+  );
+}
+
+#[test]
+fn round_trip() {
+  let errors = DbEnumErrors {
+    errors: vec![
+      drift_report(),
+      DbEnumError {
+        rust_enum: "PokemonTypes".to_string(),
+        db_source: DbEnumSource::CustomEnum("pokemon_type".to_string()),
+        errors: vec![ErrorKind::SourceNotFound(
+          "type \"pokemon_type\" does not exist".to_string(),
+        )],
+      },
+    ],
+  };
+
+  let json = serde_json::to_string(&errors).unwrap();
+
+  assert_eq!(serde_json::from_str::<DbEnumErrors>(&json).unwrap(), errors);
+}
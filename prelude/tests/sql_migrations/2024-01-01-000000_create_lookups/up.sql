@@ -0,0 +1,17 @@
+CREATE TYPE element AS ENUM ('fire', 'water', 'electric');
+
+CREATE TABLE rarities (
+  id SERIAL PRIMARY KEY,
+  name TEXT NOT NULL UNIQUE
+);
+
+INSERT INTO rarities (name) VALUES ('common'), ('rare');
+
+CREATE TABLE "regions" (
+  code TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  CONSTRAINT unique_name UNIQUE (name)
+);
+
+/* The codes are stored in uppercase */
+INSERT INTO regions VALUES ('KAN', 'kanto'), ('JOH', 'johto');
@@ -0,0 +1,8 @@
+ALTER TYPE element ADD VALUE 'grass' BEFORE 'water';
+ALTER TYPE element ADD VALUE IF NOT EXISTS 'fire';
+ALTER TYPE element RENAME VALUE 'electric' TO 'thunder';
+
+INSERT INTO rarities (id, name) VALUES (5, 'legendary');
+INSERT INTO rarities (name) VALUES ('mythical');
+
+INSERT INTO regions (name, code) VALUES ('hoenn''s', 'HOE');
@@ -0,0 +1,75 @@
+use diesel::{prelude::*, SqliteConnection};
+use diesel_enums::{DbEnumError, RunnerConfig};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+
+// The tables used by the tests that need their own data, which are created and seeded by these migrations
+const FIXTURES_MIGRATIONS: EmbeddedMigrations = embed_migrations!("tests/sqlite_migrations");
+
+diesel::table! {
+  rarities (id) {
+    id -> Integer,
+    name -> Text,
+    color -> Text,
+    weight -> Integer,
+    is_rare -> Bool,
+  }
+}
+
+diesel::table! {
+  regions (code) {
+    code -> Text,
+    name -> Text,
+  }
+}
+
+diesel::table! {
+  trainers (id) {
+    id -> Integer,
+    region -> Text,
+  }
+}
+
+diesel::table! {
+  statuses (id) {
+    id -> Integer,
+    name -> Text,
+  }
+}
+
+diesel::table! {
+  moods (id) {
+    id -> Integer,
+    name -> Text,
+  }
+}
+
+diesel::table! {
+  weathers (id) {
+    id -> Integer,
+    name -> Text,
+  }
+}
+
+diesel_enums::define_runner! {
+  // A temporary database with the fixtures, shared by all the checks that use it
+  pub fixtures_runner: sqlite = RunnerConfig::new()
+    .temp_database()
+    .migrations(FIXTURES_MIGRATIONS)
+    .max_size(2)
+}
+
+/// Returns a new in-memory database with the fixtures, for the tests that modify the data or need a connection directly.
+pub fn fixtures_db() -> SqliteConnection {
+  let mut conn = SqliteConnection::establish(":memory:").unwrap();
+
+  conn.run_pending_migrations(FIXTURES_MIGRATIONS).unwrap();
+
+  conn
+}
+
+/// A blocking runner that uses a new in-memory database with the fixtures.
+pub fn sync_fixtures_runner(
+  callback: impl FnOnce(&mut SqliteConnection) -> Result<(), DbEnumError>,
+) -> Result<(), DbEnumError> {
+  callback(&mut fixtures_db())
+}
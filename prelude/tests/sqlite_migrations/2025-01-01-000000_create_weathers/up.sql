@@ -0,0 +1,6 @@
+CREATE TABLE weathers (
+  id integer NOT NULL PRIMARY KEY,
+  name text NOT NULL UNIQUE
+);
+
+INSERT INTO weathers VALUES (1, 'sunny'), (2, 'rainy'), (3, 'foggy');
@@ -0,0 +1,7 @@
+DROP TABLE moves;
+DROP TABLE moods;
+DROP TABLE statuses;
+DROP TABLE trainers;
+DROP TABLE regions;
+DROP TABLE rarities;
+DROP TABLE types;
@@ -0,0 +1,45 @@
+CREATE TABLE types (
+  id integer NOT NULL PRIMARY KEY,
+  name text NOT NULL
+);
+
+CREATE TABLE rarities (
+  id integer NOT NULL PRIMARY KEY,
+  name text NOT NULL,
+  color text NOT NULL,
+  weight integer NOT NULL,
+  is_rare boolean NOT NULL
+);
+
+INSERT INTO rarities VALUES (1, 'common', 'white', 10, false), (2, 'rare', 'gold', 1, true);
+
+CREATE TABLE regions (
+  code text NOT NULL PRIMARY KEY,
+  name text NOT NULL
+);
+
+INSERT INTO regions VALUES ('KAN', 'kanto'), ('JOH', 'johto');
+
+CREATE TABLE trainers (
+  id integer NOT NULL PRIMARY KEY,
+  region text NOT NULL
+);
+
+CREATE TABLE statuses (
+  id integer NOT NULL PRIMARY KEY,
+  name text NOT NULL
+);
+
+INSERT INTO statuses VALUES (1, 'active'), (2, 'banned');
+
+CREATE TABLE moods (
+  id integer NOT NULL PRIMARY KEY,
+  name text NOT NULL
+);
+
+INSERT INTO moods VALUES (1, 'happy'), (2, 'sad');
+
+CREATE TABLE moves (
+  id integer NOT NULL PRIMARY KEY,
+  name text NOT NULL
+);
@@ -1,7 +1,9 @@
 mod sqlite_data;
+mod sqlite_fixtures;
 
 use diesel_enums::{diesel_enum, ErrorKind};
 use sqlite_data::{schema::*, *};
+use sqlite_fixtures::*;
 
 #[tokio::test]
 async fn you_shall_pass() {
@@ -166,11 +168,11 @@ mod id_mismatch {
     let e = errors.first().unwrap();
 
     if let ErrorKind::IdMismatches(items) = e {
-      let (name, expected, found) = items.first().unwrap();
+      let mismatch = items.first().unwrap();
 
-      assert_eq!(name, "Grass");
-      assert_eq!(*expected, 1);
-      assert_eq!(*found, 20);
+      assert_eq!(mismatch.variant, "Grass");
+      assert_eq!(mismatch.expected, 20);
+      assert_eq!(mismatch.found, 1);
     } else {
       panic!();
     }
@@ -245,9 +247,9 @@ mod skipped_ids {
     if let ErrorKind::IdMismatches(items) = e {
       assert_eq!(items.len(), 18);
 
-      for (i, (_, expected, found)) in items.iter().enumerate() {
-        assert_eq!(*expected, (i + 1) as i64);
-        assert_eq!(*found, (i + 11) as i64);
+      for (i, mismatch) in items.iter().enumerate() {
+        assert_eq!(mismatch.expected, (i + 11) as i128);
+        assert_eq!(mismatch.found, (i + 1) as i128);
       }
     } else {
       panic!();
@@ -316,3 +318,1097 @@ mod sqlite_queries {
     .unwrap();
   }
 }
+
+mod conversion_errors {
+  use diesel::{prelude::*, sql_types::Text};
+  use diesel_enums::{UnknownValue, UnknownVariant};
+
+  use super::models::*;
+  use crate::run_sqlite_query;
+
+  #[test]
+  fn unknown_name() {
+    assert_eq!(
+      Types::from_db_name("Fairies").unwrap_err(),
+      UnknownVariant {
+        enum_name: "Types",
+        value: UnknownValue::Name("Fairies".to_string()),
+      }
+    );
+  }
+
+  #[test]
+  fn unknown_id() {
+    assert_eq!(
+      TypesId::try_from(100).unwrap_err(),
+      UnknownVariant {
+        enum_name: "TypesId",
+        value: UnknownValue::Id(100),
+      }
+    );
+  }
+
+  #[tokio::test]
+  async fn deserialization_error() {
+    let error = run_sqlite_query(|conn| {
+      let result = diesel::select("Fairies".into_sql::<Text>()).get_result::<Types>(conn);
+
+      if let Err(diesel::result::Error::DeserializationError(e)) = result
+        && let Some(field_error) = e.downcast_ref::<diesel::result::DeserializeFieldError>()
+      {
+        Ok(field_error.error.downcast_ref::<UnknownVariant>().cloned())
+      } else {
+        Ok(None)
+      }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(
+      error.unwrap().value,
+      UnknownValue::Name("Fairies".to_string())
+    );
+  }
+}
+
+mod variants_api {
+  use super::models::*;
+
+  #[test]
+  fn variants_api() {
+    assert_eq!(Types::COUNT, 18);
+    assert_eq!(Types::VARIANTS.len(), Types::COUNT);
+    assert_eq!(Types::VARIANTS[2], Types::Fire);
+    assert_eq!(Types::DB_NAMES[2], "Fire");
+
+    for (variant, name) in Types::iter().zip(Types::DB_NAMES) {
+      assert_eq!(variant.db_name(), *name);
+    }
+
+    assert_eq!(TypesId::COUNT, Types::COUNT);
+    assert_eq!(TypesId::IDS, (1..=18).collect::<Vec<i32>>());
+
+    for (variant, id) in TypesId::iter().zip(TypesId::IDS) {
+      let variant_id: i32 = variant.into();
+
+      assert_eq!(variant_id, *id);
+    }
+  }
+}
+
+mod big_ids {
+  use super::*;
+
+  #[diesel_enum(
+    skip_check,
+    skip_ids(1..5_000_000_000),
+    id_mapping(sql_type = diesel::sql_types::BigInt)
+  )]
+  enum Snowflakes {
+    First,
+    Second,
+    #[db_mapping(id = 9_000_000_000_000_000_000)]
+    Last,
+  }
+
+  #[test]
+  fn big_ids() {
+    assert_eq!(
+      Snowflakes::IDS,
+      [5_000_000_000, 5_000_000_001, 9_000_000_000_000_000_000]
+    );
+
+    let last_id: i64 = Snowflakes::Last.into();
+
+    assert_eq!(last_id, 9_000_000_000_000_000_000);
+    assert_eq!(
+      Snowflakes::try_from(5_000_000_001i64).unwrap(),
+      Snowflakes::Second
+    );
+    assert!(Snowflakes::try_from(1i64).is_err());
+  }
+}
+
+mod unsigned_ids {
+  use super::*;
+
+  #[diesel_enum(
+    skip_check,
+    id_mapping(sql_type = diesel::sql_types::Unsigned<diesel::sql_types::Integer>)
+  )]
+  enum Types {
+    Grass,
+    #[db_mapping(id = 4_000_000_000)]
+    Fire,
+  }
+
+  #[diesel_enum(
+    skip_check,
+    id_mapping(sql_type = diesel::sql_types::Unsigned<diesel::sql_types::TinyInt>)
+  )]
+  #[repr(u8)]
+  enum Rarities {
+    Common,
+    Rare = 255,
+  }
+
+  #[test]
+  fn unsigned_ids() {
+    assert_eq!(Types::IDS, [1u32, 4_000_000_000]);
+    assert_eq!(Types::try_from(4_000_000_000u32).unwrap(), Types::Fire);
+
+    let error = Rarities::try_from(3u8).unwrap_err();
+
+    assert_eq!(error.value, diesel_enums::UnknownValue::Id(3));
+    assert_eq!(Rarities::IDS, [1u8, 255]);
+
+    let rare_id: u8 = Rarities::Rare.into();
+
+    assert_eq!(rare_id, Rarities::Rare as u8);
+  }
+}
+
+mod discriminants {
+  use super::*;
+
+  #[diesel_enum(skip_check, id_mapping(default))]
+  #[repr(i16, align(4))]
+  enum Types {
+    Frozen = -1,
+    Fire = 3,
+    Ice,
+    #[db_mapping(id = 7)]
+    Water = 7,
+  }
+
+  // Only the integer reprs are used for the mapping
+  #[diesel_enum(skip_check, id_mapping(default))]
+  #[repr(C)]
+  enum Moods {
+    Happy = 5,
+    Sad,
+  }
+
+  // The fallback variant is still counted when numbering the variants after it
+  #[diesel_enum(skip_check, id_mapping(default))]
+  enum Weathers {
+    Sunny = 5,
+    #[db_mapping(other)]
+    Unknown,
+    Rainy,
+  }
+
+  #[test]
+  fn discriminants() {
+    assert_eq!(Types::IDS, [-1i16, 3, 4, 7]);
+
+    let ice_id: i16 = Types::Ice.into();
+
+    assert_eq!(ice_id, Types::Ice as i16);
+    assert_eq!(Types::try_from(-1i16).unwrap(), Types::Frozen);
+
+    assert_eq!(Moods::IDS, [5i32, 6]);
+
+    assert_eq!(Weathers::IDS, [5, 7]);
+    assert_eq!(i32::try_from(Weathers::Rainy).unwrap(), Weathers::Rainy as i32);
+  }
+}
+
+mod generated_sql {
+  use diesel::prelude::*;
+
+  use super::{models::*, *};
+
+  #[diesel_enum(skip_check, table_name = "rarities", column = "label", name_mapping(default))]
+  enum Rarities {
+    Common,
+    #[db_mapping(name = "it's")]
+    Rare,
+  }
+
+  #[test]
+  fn seed_sql() {
+    assert_eq!(
+      Rarities::seed_sql(),
+      "INSERT INTO rarities (label) VALUES ('common'), ('it''s');"
+    );
+
+    assert!(
+      Types::seed_sql().starts_with("INSERT INTO types (id, name) VALUES (1, 'Grass'), (2, 'Poison'),")
+    );
+
+    let mut conn = fixtures_db();
+
+    diesel::sql_query(Types::seed_sql())
+      .execute(&mut conn)
+      .unwrap();
+
+    let rows: Vec<(TypesId, Types)> = types::table
+      .select((types::id, types::name))
+      .order_by(types::id)
+      .load(&mut conn)
+      .unwrap();
+
+    assert_eq!(rows, TypesId::iter().zip(Types::iter()).collect::<Vec<_>>());
+  }
+}
+
+mod sync_to_db {
+  use diesel::prelude::*;
+  use diesel_enums::{SyncPolicy, SyncReport, UpdatedCode, UpdatedId};
+
+  use super::{models::*, *};
+
+  #[diesel_enum(conn = diesel_enums::sqlite_runner, skip_test, table = types, case = "PascalCase", name_mapping(default))]
+  enum TypeNames {
+    Grass,
+    Fire,
+  }
+
+  // The codes of the fixtures are swapped
+  #[diesel_enum(skip_check, table = regions, name_mapping(default), code_mapping(default))]
+  enum Regions {
+    #[db_mapping(code = "JOH")]
+    Kanto,
+    #[db_mapping(code = "KAN")]
+    Johto,
+  }
+
+  // The fixtures have `gold` and `1` for the rare variant
+  #[diesel_enum(skip_check, table = rarities, extra_columns(color: Text, weight: Integer, is_rare: Bool), name_mapping(default), id_mapping(default))]
+  enum Rarities {
+    #[db_mapping(extra(color = "white", weight = 10, is_rare = false))]
+    Common,
+    #[db_mapping(extra(color = "purple", weight = 2, is_rare = true))]
+    Rare,
+  }
+
+  #[test]
+  fn sync_with_ids() {
+    let mut conn = fixtures_db();
+
+    diesel::insert_into(types::table)
+      .values([(1, "Grass"), (50, "Poison"), (99, "Unknown")].map(|(id, name)| (types::id.eq(id), types::name.eq(name))))
+      .execute(&mut conn)
+      .unwrap();
+
+    let report = Types::sync_to_db(&mut conn, SyncPolicy::InsertOnly).unwrap();
+
+    assert_eq!(report.inserted.len(), 16);
+    assert!(!report.inserted.contains(&"Grass".to_string()));
+    assert!(report.deleted.is_empty());
+    assert!(report.updated_ids.is_empty());
+
+    let report = Types::sync_to_db(&mut conn, SyncPolicy::Full).unwrap();
+
+    assert_eq!(
+      report,
+      SyncReport {
+        inserted: vec![],
+        deleted: vec!["Unknown".to_string()],
+        updated_ids: vec![UpdatedId {
+          name: "Poison".to_string(),
+          old_id: 50,
+          new_id: 2,
+        }],
+        ..Default::default()
+      }
+    );
+
+    let rows: Vec<(TypesId, Types)> = types::table
+      .select((types::id, types::name))
+      .order_by(types::id)
+      .load(&mut conn)
+      .unwrap();
+
+    assert_eq!(rows, TypesId::iter().zip(Types::iter()).collect::<Vec<_>>());
+
+    assert!(Types::sync_to_db(&mut conn, SyncPolicy::Full).unwrap().is_empty());
+  }
+
+  #[test]
+  fn sync_without_ids() {
+    let mut conn = fixtures_db();
+
+    diesel::insert_into(types::table)
+      .values([(1, "Grass"), (2, "Water"), (3, "Farfetch'd")].map(|(id, name)| (types::id.eq(id), types::name.eq(name))))
+      .execute(&mut conn)
+      .unwrap();
+
+    let report = TypeNames::sync_to_db(&mut conn, SyncPolicy::Full).unwrap();
+
+    assert_eq!(
+      report,
+      SyncReport {
+        inserted: vec!["Fire".to_string()],
+        deleted: vec!["Water".to_string(), "Farfetch'd".to_string()],
+        ..Default::default()
+      }
+    );
+  }
+
+  #[test]
+  fn swapped_ids() {
+    let mut conn = fixtures_db();
+
+    diesel::insert_into(types::table)
+      .values([(1, "Poison"), (2, "Grass")].map(|(id, name)| (types::id.eq(id), types::name.eq(name))))
+      .execute(&mut conn)
+      .unwrap();
+
+    let report = Types::sync_to_db(&mut conn, SyncPolicy::Full).unwrap();
+
+    assert_eq!(
+      report.updated_ids,
+      [
+        UpdatedId {
+          name: "Grass".to_string(),
+          old_id: 2,
+          new_id: 1,
+        },
+        UpdatedId {
+          name: "Poison".to_string(),
+          old_id: 1,
+          new_id: 2,
+        },
+      ]
+    );
+
+    let rows: Vec<(TypesId, Types)> = types::table
+      .select((types::id, types::name))
+      .order_by(types::id)
+      .load(&mut conn)
+      .unwrap();
+
+    assert_eq!(rows, TypesId::iter().zip(Types::iter()).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn swapped_codes() {
+    let mut conn = fixtures_db();
+
+    // The mismatched codes are only fixed with the full policy
+    assert!(Regions::sync_to_db(&mut conn, SyncPolicy::InsertOnly).unwrap().is_empty());
+
+    let report = Regions::sync_to_db(&mut conn, SyncPolicy::Full).unwrap();
+
+    assert_eq!(
+      report,
+      SyncReport {
+        updated_codes: vec![
+          UpdatedCode {
+            name: "kanto".to_string(),
+            old_code: "KAN".to_string(),
+            new_code: "JOH".to_string(),
+          },
+          UpdatedCode {
+            name: "johto".to_string(),
+            old_code: "JOH".to_string(),
+            new_code: "KAN".to_string(),
+          },
+        ],
+        ..Default::default()
+      }
+    );
+
+    let rows: Vec<(RegionsCode, Regions)> = regions::table
+      .select((regions::code, regions::name))
+      .order_by(regions::name.desc())
+      .load(&mut conn)
+      .unwrap();
+
+    assert_eq!(rows, RegionsCode::iter().zip(Regions::iter()).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn changed_extra_values() {
+    let mut conn = fixtures_db();
+
+    assert!(Rarities::sync_to_db(&mut conn, SyncPolicy::InsertOnly).unwrap().is_empty());
+
+    let report = Rarities::sync_to_db(&mut conn, SyncPolicy::Full).unwrap();
+
+    assert_eq!(
+      report,
+      SyncReport {
+        updated_values: vec!["rare".to_string()],
+        ..Default::default()
+      }
+    );
+
+    let rows: Vec<(String, i32, bool)> = rarities::table
+      .select((rarities::color, rarities::weight, rarities::is_rare))
+      .order_by(rarities::id)
+      .load(&mut conn)
+      .unwrap();
+
+    assert_eq!(
+      rows,
+      [("white".to_string(), 10, false), ("purple".to_string(), 2, true)]
+    );
+
+    assert!(Rarities::sync_to_db(&mut conn, SyncPolicy::Full).unwrap().is_empty());
+  }
+}
+
+mod fallback {
+  use diesel::{prelude::*, sql_types::Text};
+  use diesel_enums::FallbackVariantError;
+
+  use super::*;
+  use crate::run_sqlite_query;
+
+  #[diesel_enum(conn = diesel_enums::sqlite_runner, table = types, case = "PascalCase", name_mapping(default), id_mapping(default))]
+  enum Elements {
+    Grass,
+    Poison,
+    #[db_mapping(other)]
+    Other,
+  }
+
+  #[test]
+  fn fallback_conversions() {
+    assert_eq!(Elements::VARIANTS, [Elements::Grass, Elements::Poison]);
+    assert_eq!(Elements::COUNT, 2);
+    assert_eq!(Elements::from_db_name("Fire").unwrap(), Elements::Other);
+    assert_eq!(ElementsId::try_from(3).unwrap(), ElementsId::Other);
+    assert_eq!(ElementsId::IDS, [1, 2]);
+
+    assert_eq!(Elements::Grass.db_name(), Some("Grass"));
+    assert_eq!(Elements::Other.db_name(), None);
+    assert_eq!(i32::try_from(ElementsId::Poison).unwrap(), 2);
+    assert_eq!(
+      i32::try_from(ElementsId::Other).unwrap_err(),
+      FallbackVariantError {
+        enum_name: "ElementsId",
+        variant: "Other",
+      }
+    );
+  }
+
+  #[tokio::test]
+  async fn fallback_queries() {
+    let (names, error) = run_sqlite_query(|conn| {
+      let names: Vec<Elements> = types::table
+        .select(types::name)
+        .order_by(types::id)
+        .load(conn)?;
+
+      let result = diesel::select(Elements::Other.into_sql::<Text>()).get_result::<String>(conn);
+
+      if let Err(diesel::result::Error::SerializationError(e)) = result {
+        Ok((names, e.downcast_ref::<FallbackVariantError>().cloned()))
+      } else {
+        Ok((names, None))
+      }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(names[..3], [Elements::Grass, Elements::Poison, Elements::Other]);
+    assert_eq!(
+      error.unwrap(),
+      FallbackVariantError {
+        enum_name: "Elements",
+        variant: "Other",
+      }
+    );
+  }
+
+  #[tokio::test]
+  async fn fallback_warning() {
+    let unmapped_values = Elements::check_consistency().await.unwrap().unwrap();
+
+    assert_eq!(unmapped_values.fallback, "Other");
+    assert_eq!(
+      unmapped_values.values,
+      [
+        "Bug", "Dark", "Dragon", "Electric", "Fairy", "Fighting", "Fire", "Flying", "Ghost", "Ground", "Ice",
+        "Normal", "Psychic", "Rock", "Steel", "Water"
+      ]
+    );
+  }
+}
+
+mod extra_columns {
+  use diesel::prelude::*;
+
+  use super::*;
+
+  #[diesel_enum(conn = fixtures_runner, table = rarities, extra_columns(color: Text, weight: Integer, is_rare: Bool), name_mapping(default), id_mapping(default))]
+  enum Rarities {
+    #[db_mapping(extra(color = "white", weight = 10, is_rare = false))]
+    Common,
+    #[db_mapping(extra(color = "gold", weight = 1, is_rare = true))]
+    Rare,
+  }
+
+  mod wrong_values {
+    use super::*;
+
+    #[diesel_enum(conn = fixtures_runner, skip_test, table = rarities, extra_columns(color: Text, weight: Integer), name_mapping(default))]
+    enum Rarities {
+      #[db_mapping(extra(color = "white", weight = 10))]
+      Common,
+      #[db_mapping(extra(weight = 5, color = "silver"))]
+      Rare,
+    }
+
+    #[tokio::test]
+    async fn wrong_values() {
+      let errors = Rarities::check_consistency().await.unwrap_err().errors;
+
+      assert_eq!(
+        errors,
+        [
+          ErrorKind::ValueMismatch {
+            variant: "rare".to_string(),
+            column: "color".to_string(),
+            expected: "\"silver\"".to_string(),
+            found: "\"gold\"".to_string(),
+          },
+          ErrorKind::ValueMismatch {
+            variant: "rare".to_string(),
+            column: "weight".to_string(),
+            expected: "5".to_string(),
+            found: "1".to_string(),
+          },
+        ]
+      );
+    }
+  }
+
+  #[test]
+  fn accessors() {
+    assert_eq!(Rarities::Rare.color(), "gold");
+    assert_eq!(Rarities::Common.weight(), 10);
+    assert!(RaritiesId::Rare.is_rare());
+  }
+
+  #[test]
+  fn seed_sql() {
+    assert_eq!(
+      Rarities::seed_sql(),
+      "INSERT INTO rarities (id, name, color, weight, is_rare) VALUES (1, 'common', 'white', 10, FALSE), (2, 'rare', 'gold', 1, TRUE);"
+    );
+
+    let mut conn = fixtures_db();
+
+    diesel::delete(rarities::table).execute(&mut conn).unwrap();
+
+    diesel::sql_query(Rarities::seed_sql())
+      .execute(&mut conn)
+      .unwrap();
+
+    let rows: Vec<(String, bool)> = rarities::table
+      .select((rarities::name, rarities::is_rare))
+      .load(&mut conn)
+      .unwrap();
+
+    assert_eq!(rows, [("common".to_string(), false), ("rare".to_string(), true)]);
+  }
+}
+
+mod codes {
+  use diesel::prelude::*;
+  use diesel_enums::{UnknownValue, UnknownVariant};
+
+  use super::*;
+
+  #[diesel_enum(conn = fixtures_runner, table = regions, snapshot = "tests/snapshots/regions.json", name_mapping(default), code_mapping(default))]
+  enum Regions {
+    #[db_mapping(code = "KAN")]
+    Kanto,
+    #[db_mapping(code = "JOH")]
+    Johto,
+  }
+
+  mod wrong_codes {
+    use super::*;
+
+    #[diesel_enum(conn = fixtures_runner, skip_test, table = regions, code_mapping(default))]
+    enum Regions {
+      #[db_mapping(code = "KAN")]
+      Kanto,
+      #[db_mapping(code = "JHT")]
+      Johto,
+    }
+
+    #[tokio::test]
+    async fn wrong_codes() {
+      let errors = Regions::check_consistency().await.unwrap_err().errors;
+
+      assert_eq!(
+        errors,
+        [ErrorKind::ValueMismatch {
+          variant: "johto".to_string(),
+          column: "code".to_string(),
+          expected: "\"JHT\"".to_string(),
+          found: "\"JOH\"".to_string(),
+        }]
+      );
+    }
+  }
+
+  #[test]
+  fn codes() {
+    assert_eq!(RegionsCode::CODES, ["KAN", "JOH"]);
+    assert_eq!(RegionsCode::Johto.code(), "JOH");
+    assert_eq!(RegionsCode::from_code("KAN").unwrap(), RegionsCode::Kanto);
+    assert_eq!(
+      RegionsCode::from_code("HOE").unwrap_err(),
+      UnknownVariant {
+        enum_name: "RegionsCode",
+        value: UnknownValue::Code("HOE".to_string()),
+      }
+    );
+    assert_eq!(Regions::from(RegionsCode::Johto), Regions::Johto);
+  }
+
+  #[test]
+  fn seed_sql() {
+    assert_eq!(
+      Regions::seed_sql(),
+      "INSERT INTO regions (code, name) VALUES ('KAN', 'kanto'), ('JOH', 'johto');"
+    );
+  }
+
+  #[test]
+  fn query_round_trip() {
+    let mut conn = fixtures_db();
+
+    diesel::insert_into(trainers::table)
+      .values((trainers::id.eq(1), trainers::region.eq(RegionsCode::Johto)))
+      .execute(&mut conn)
+      .unwrap();
+
+    let stored: String = trainers::table
+      .select(trainers::region)
+      .first(&mut conn)
+      .unwrap();
+
+    assert_eq!(stored, "JOH");
+
+    let region: RegionsCode = trainers::table
+      .select(trainers::region)
+      .first(&mut conn)
+      .unwrap();
+
+    assert_eq!(region, RegionsCode::Johto);
+  }
+}
+
+mod runtime_check {
+  use diesel::prelude::*;
+  use diesel_enums::{DbEnumError, DbEnumSource};
+
+  use super::*;
+
+  #[diesel_enum(skip_check, runtime_check = SqliteConnection, table = statuses, name_mapping(default), id_mapping(default))]
+  enum Statuses {
+    Active,
+    Banned,
+  }
+
+  mod missing_variant {
+    use super::*;
+
+    #[diesel_enum(skip_check, runtime_check = SqliteConnection, table = statuses, name_mapping(default))]
+    pub enum Statuses {
+      Active,
+      Banned,
+      Deleted,
+    }
+  }
+
+  #[test]
+  fn runtime_check() {
+    let mut conn = fixtures_db();
+
+    Statuses::check_consistency_with(&mut conn).unwrap();
+
+    let errors = missing_variant::Statuses::check_consistency_with(&mut conn)
+      .unwrap_err()
+      .errors;
+
+    assert_eq!(errors, [ErrorKind::MissingFromDb(vec!["deleted".to_string()])]);
+  }
+
+  #[test]
+  fn check_all() {
+    let mut conn = fixtures_db();
+
+    // Other test files in the same binary may register their own enums, so only the ones defined here are checked
+    let is_tested = |rust_enum: &str| matches!(rust_enum, "Statuses" | "StatusesId");
+
+    let mut registered: Vec<&str> = diesel_enums::registered_enums()
+      .map(|registered| registered.rust_enum)
+      .filter(|rust_enum| is_tested(rust_enum))
+      .collect();
+    registered.sort();
+
+    assert_eq!(registered, ["Statuses", "StatusesId"]);
+
+    let errors: Vec<DbEnumError> = diesel_enums::check_all(&mut conn)
+      .unwrap_err()
+      .errors
+      .into_iter()
+      .filter(|error| is_tested(&error.rust_enum))
+      .collect();
+
+    assert_eq!(
+      errors,
+      [DbEnumError {
+        rust_enum: "Statuses".to_string(),
+        db_source: DbEnumSource::Column {
+          table: "statuses".to_string(),
+          column: "name".to_string(),
+        },
+        errors: vec![ErrorKind::MissingFromDb(vec!["deleted".to_string()])],
+      }]
+    );
+
+    // The enums that use a different type of connection are skipped
+    assert!(diesel_enums::check_all(&mut ())
+      .unwrap()
+      .iter()
+      .all(|warning| !is_tested(&warning.rust_enum)));
+  }
+}
+
+mod snapshots {
+  use diesel::prelude::*;
+  use diesel_enums::{
+    snapshots::{read_snapshot, write_snapshot, EnumSnapshot, SnapshotVariant},
+    DbEnumError, IdMismatch,
+  };
+
+  use super::*;
+
+  diesel::table! {
+    elements (id) {
+      id -> Integer,
+      name -> Text,
+    }
+  }
+
+  async fn no_db(
+    _callback: impl FnOnce(&mut SqliteConnection) -> Result<(), DbEnumError>,
+  ) -> Result<(), DbEnumError> {
+    panic!("The database should not be used in offline mode")
+  }
+
+  #[diesel_enum(conn = no_db, skip_test, snapshot = "tests/snapshots/elements.json", table = elements, name_mapping(default), id_mapping(default))]
+  enum Elements {
+    Fire,
+    Water,
+    #[db_mapping(id = 4)]
+    Grass,
+  }
+
+  mod wrong_snapshot {
+    use super::*;
+
+    #[diesel_enum(conn = no_db, skip_test, snapshot = "tests/snapshots/elements.json", table = elements, name_mapping(default), id_mapping(default))]
+    pub enum Elements {
+      Fire,
+      Water,
+      Grass,
+      Electric,
+    }
+  }
+
+  mod missing_snapshot {
+    use super::*;
+
+    #[diesel_enum(conn = no_db, skip_test, snapshot = "tests/snapshots/missing.json", table = elements, name_mapping(default))]
+    pub enum Elements {
+      Fire,
+      Water,
+    }
+  }
+
+  #[test]
+  fn offline_checks() {
+    Elements::check_consistency_offline().unwrap();
+
+    let errors = wrong_snapshot::Elements::check_consistency_offline()
+      .unwrap_err()
+      .errors;
+
+    assert_eq!(
+      errors,
+      [
+        ErrorKind::IdMismatches(vec![IdMismatch {
+          variant: "grass".to_string(),
+          expected: 3,
+          found: 4,
+        }]),
+        ErrorKind::MissingFromDb(vec!["electric".to_string()]),
+      ]
+    );
+  }
+
+  #[test]
+  fn snapshot_files() {
+    let path = std::env::temp_dir().join("diesel_enums_snapshot_test/elements.json");
+
+    let snapshot = EnumSnapshot {
+      variants: vec![
+        SnapshotVariant {
+          id: Some(1),
+          ..SnapshotVariant::new("fire".to_string())
+        },
+        SnapshotVariant::new("water".to_string()),
+      ],
+    };
+
+    write_snapshot(&path, &snapshot).unwrap();
+
+    assert_eq!(read_snapshot(&path).unwrap(), snapshot);
+    assert_eq!(snapshot.variants[0].id::<u8>(&path).unwrap(), 1);
+    assert!(matches!(
+      snapshot.variants[1].id::<u8>(&path).unwrap_err().errors.as_slice(),
+      [ErrorKind::QueryFailed { .. }]
+    ));
+  }
+
+  #[test]
+  fn missing_snapshot() {
+    let error = missing_snapshot::Elements::check_consistency_offline().unwrap_err();
+
+    assert_eq!(error.rust_enum, "Elements");
+    assert!(matches!(error.errors.as_slice(), [ErrorKind::SourceNotFound(message)] if message.contains("missing.json")));
+  }
+}
+
+mod test_runtimes {
+  use diesel::prelude::*;
+  use diesel_enums::DbEnumError;
+
+  use super::*;
+
+  async fn multi_thread_runner(
+    callback: impl FnOnce(&mut SqliteConnection) -> Result<(), DbEnumError> + Send + 'static,
+  ) -> Result<(), DbEnumError> {
+    assert_eq!(
+      tokio::runtime::Handle::current().runtime_flavor(),
+      tokio::runtime::RuntimeFlavor::MultiThread
+    );
+
+    tokio::task::spawn_blocking(|| sync_fixtures_runner(callback))
+      .await
+      .unwrap()
+  }
+
+  #[diesel_enum(conn = sync_fixtures_runner, test_runtime = "sync", table = moods, name_mapping(default), id_mapping(default))]
+  enum Moods {
+    Happy,
+    Sad,
+  }
+
+  mod multi_thread {
+    use super::*;
+
+    #[diesel_enum(conn = multi_thread_runner, test_runtime = "tokio_multi_thread", table = moods, name_mapping(default))]
+    pub enum Moods {
+      Happy,
+      Sad,
+    }
+  }
+
+  mod missing_variant {
+    use super::*;
+
+    #[diesel_enum(conn = sync_fixtures_runner, test_runtime = "sync", skip_test, table = moods, name_mapping(default))]
+    pub enum Moods {
+      Happy,
+      Sad,
+      Angry,
+    }
+  }
+
+  #[test]
+  fn sync_runtime() {
+    Moods::check_consistency().unwrap();
+
+    let errors = missing_variant::Moods::check_consistency()
+      .unwrap_err()
+      .errors;
+
+    assert_eq!(errors, [ErrorKind::MissingFromDb(vec!["angry".to_string()])]);
+  }
+}
+
+mod custom_runner {
+  use diesel_enums::RunnerConfig;
+
+  use super::*;
+
+  diesel::table! {
+    seasons (id) {
+      id -> Integer,
+      name -> Text,
+    }
+  }
+
+  diesel_enums::define_runner! {
+    // A single in-memory connection, which keeps the table created by the setup SQL
+    memory_runner: sqlite = RunnerConfig::new()
+      .database_url(":memory:")
+      .setup_sql("CREATE TABLE seasons (id integer NOT NULL PRIMARY KEY, name text NOT NULL);")
+      .setup_sql("INSERT INTO seasons VALUES (1, 'spring'), (2, 'summer');")
+  }
+
+  #[diesel_enum(conn = memory_runner, table = seasons, name_mapping(default), id_mapping(default))]
+  enum Seasons {
+    Spring,
+    Summer,
+  }
+
+  mod missing_variant {
+    use super::*;
+
+    #[diesel_enum(conn = memory_runner, skip_test, table = seasons, name_mapping(default))]
+    pub enum Seasons {
+      Spring,
+      Summer,
+      Autumn,
+    }
+  }
+
+  #[tokio::test]
+  async fn custom_runner() {
+    let errors = missing_variant::Seasons::check_consistency()
+      .await
+      .unwrap_err()
+      .errors;
+
+    assert_eq!(errors, [ErrorKind::MissingFromDb(vec!["autumn".to_string()])]);
+  }
+}
+
+mod temp_database {
+  use super::*;
+
+  #[diesel_enum(conn = fixtures_runner, table = weathers, name_mapping(default), id_mapping(default))]
+  enum Weathers {
+    Sunny,
+    Rainy,
+    Foggy,
+  }
+
+  mod missing_variant {
+    use super::*;
+
+    #[diesel_enum(conn = fixtures_runner, skip_test, table = weathers, name_mapping(default))]
+    pub enum Weathers {
+      Sunny,
+      Rainy,
+      Foggy,
+      Snowy,
+    }
+  }
+
+  #[tokio::test]
+  async fn migrated_temp_database() {
+    let errors = missing_variant::Weathers::check_consistency()
+      .await
+      .unwrap_err()
+      .errors;
+
+    assert_eq!(errors, [ErrorKind::MissingFromDb(vec!["snowy".to_string()])]);
+  }
+}
+
+mod failed_checks {
+  use diesel_enums::{DbEnumSource, RunnerConfig};
+
+  use super::*;
+
+  diesel::table! {
+    berries (id) {
+      id -> Integer,
+      name -> Text,
+    }
+  }
+
+  // The `moves` table of the fixtures has a `name` column instead
+  diesel::table! {
+    moves (id) {
+      id -> Integer,
+      title -> Text,
+    }
+  }
+
+  diesel_enums::define_runner! {
+    unset_runner: sqlite = RunnerConfig::new().database_url_env("DIESEL_ENUMS_UNSET_DATABASE_URL")
+  }
+
+  mod missing_table {
+    use super::*;
+
+    #[diesel_enum(conn = fixtures_runner, skip_test, table = berries, name_mapping(default))]
+    pub enum Berries {
+      Oran,
+      Sitrus,
+    }
+  }
+
+  mod missing_column {
+    use super::*;
+
+    #[diesel_enum(conn = fixtures_runner, skip_test, table = moves, column = "title", name_mapping(default))]
+    pub enum Moves {
+      Tackle,
+      Growl,
+    }
+  }
+
+  mod connection_failure {
+    use super::*;
+
+    #[diesel_enum(conn = unset_runner, skip_test, table = berries, name_mapping(default))]
+    pub enum Berries {
+      Oran,
+      Sitrus,
+    }
+  }
+
+  #[tokio::test]
+  async fn missing_table() {
+    let error = missing_table::Berries::check_consistency().await.unwrap_err();
+
+    assert_eq!(
+      error.errors,
+      [ErrorKind::SourceNotFound("the table `berries` does not exist".to_string())]
+    );
+  }
+
+  #[tokio::test]
+  async fn missing_column() {
+    let error = missing_column::Moves::check_consistency().await.unwrap_err();
+
+    assert_eq!(
+      error.errors,
+      [ErrorKind::SourceNotFound("the column `moves.title` does not exist".to_string())]
+    );
+  }
+
+  #[tokio::test]
+  async fn connection_failure() {
+    let error = connection_failure::Berries::check_consistency()
+      .await
+      .unwrap_err();
+
+    assert_eq!(error.rust_enum, "Berries");
+    assert_eq!(
+      error.db_source,
+      DbEnumSource::Column {
+        table: "berries".to_string(),
+        column: "name".to_string()
+      }
+    );
+    assert!(matches!(error.errors.as_slice(), [ErrorKind::ConnectionFailed(message)] if message.contains("DIESEL_ENUMS_UNSET_DATABASE_URL")));
+  }
+}
@@ -0,0 +1,17 @@
+use diesel_enums::diesel_enum;
+
+#[diesel_enum(skip_check, id_mapping(default))]
+enum Types {
+  Grass = 1,
+  #[db_mapping(id = 5)]
+  Fire = 3,
+}
+
+#[diesel_enum(skip_check, id_mapping(sql_type = diesel::sql_types::BigInt))]
+#[repr(i16)]
+enum Rarities {
+  Common,
+  Rare,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use diesel_enums::diesel_enum;
+
+#[diesel_enum(skip_check, id_mapping(default))]
+enum Types {
+  Grass,
+  #[db_mapping(id = 1)]
+  Poison,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use diesel_enums::diesel_enum;
+
+#[diesel_enum(skip_check, case = "UPPERCASE", name_mapping(default))]
+enum Types {
+  Grass,
+  #[db_mapping(name = "GRASS")]
+  Poison,
+  #[db_mapping(aliases("FLAME", "GRASS"))]
+  Fire,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use diesel_enums::diesel_enum;
+
+#[diesel_enum(skip_check, skip_ids(1..127), id_mapping(sql_type = diesel::sql_types::TinyInt))]
+enum Types {
+  Grass,
+  Poison,
+  #[db_mapping(id = 200)]
+  Fire,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use diesel_enums::diesel_enum;
+
+#[diesel_enum(skip_check, id_mapping(sql_type = diesel::sql_types::BigInt))]
+enum Snowflakes {
+  First,
+  #[db_mapping(id = 9_223_372_036_854_775_808)]
+  Last,
+}
+
+fn main() {}
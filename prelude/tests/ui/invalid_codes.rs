@@ -0,0 +1,18 @@
+use diesel_enums::diesel_enum;
+
+#[diesel_enum(skip_check, code_mapping(default))]
+enum Regions {
+  #[db_mapping(code = "KAN")]
+  Kanto,
+  #[db_mapping(code = "KAN")]
+  Johto,
+  Hoenn,
+}
+
+#[diesel_enum(skip_check, name_mapping(default))]
+enum Types {
+  #[db_mapping(code = "GRS")]
+  Grass,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use diesel_enums::diesel_enum;
+
+#[diesel_enum(skip_check, extra_columns(color: Text, weight: Integer), name_mapping(default))]
+enum Rarities {
+  #[db_mapping(extra(color = "white"))]
+  Common,
+  #[db_mapping(extra(color = "gold", weight = 1, size = 3))]
+  Rare,
+}
+
+fn main() {}
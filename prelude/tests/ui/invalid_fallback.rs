@@ -0,0 +1,12 @@
+use diesel_enums::diesel_enum;
+
+#[diesel_enum(skip_check, name_mapping(default))]
+enum Types {
+  Grass,
+  #[db_mapping(other, name = "unknown")]
+  Unknown,
+  #[db_mapping(other)]
+  Other,
+}
+
+fn main() {}
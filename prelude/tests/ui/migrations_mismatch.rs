@@ -0,0 +1,27 @@
+use diesel_enums::diesel_enum;
+
+#[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+#[diesel(postgres_type(name = "element"))]
+pub struct Element;
+
+#[diesel_enum(migrations = "../sql_migrations", check_order, name_mapping(path = Element))]
+pub enum Elements {
+  Fire,
+  Water,
+  Grass,
+  Electric,
+}
+
+#[diesel_enum(migrations = "../sql_migrations", id_mapping(default))]
+pub enum Rarities {
+  Common,
+  Rare,
+  Legendary,
+}
+
+#[diesel_enum(migrations = "../sql_migrations", table_name = "pokemons", name_mapping(default))]
+pub enum Pokemons {
+  Bulbasaur,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use diesel_enums::diesel_enum;
+
+#[diesel_enum(skip_check, skip_ids(2..4), id_mapping(default))]
+enum Types {
+  Grass,
+  Fire = 5,
+  Water,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use diesel_enums::diesel_enum;
+
+#[diesel_enum(skip_check, skip_ids(1..=3), id_mapping(default))]
+enum Types {
+  Grass,
+  #[db_mapping(id = 2)]
+  Poison,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use diesel_enums::diesel_enum;
+
+#[diesel_enum(skip_check, id_mapping(default))]
+#[repr(u8)]
+enum Rarities {
+  Common,
+  Rare,
+}
+
+fn main() {}
@@ -3,7 +3,8 @@ use std::ops::Range;
 use convert_case::{Case, Casing};
 use quote::{format_ident, quote, ToTokens};
 use syn::{
-  parse::Parse, punctuated::Punctuated, Error, Expr, Ident, Lit, Meta, Path, RangeLimits, Token,
+  parse::Parse, punctuated::Punctuated, Attribute, Error, Expr, GenericArgument, Ident, Lit, Meta,
+  Path, PathArguments, RangeLimits, Token, Type, UnOp,
 };
 
 use crate::{Check, TokenStream2};
@@ -13,16 +14,206 @@ pub struct Attributes<'a> {
   pub table_name: Option<String>,
   pub column: Option<String>,
   pub conn: Check,
+  pub runtime_check: Option<TokenStream2>,
+  pub snapshot: Option<String>,
+  pub migrations: Option<(Ident, String)>,
+  pub test_runtime: TestRuntime,
   pub skip_test: bool,
+  pub check_order: bool,
   pub case: Case<'a>,
   pub name_mapping: Option<NameMapping>,
   pub id_mapping: Option<IdMapping>,
-  pub skip_ranges: Vec<Range<i32>>,
+  pub code_mapping: Option<CodeMapping>,
+  pub skip_ranges: Vec<Range<i128>>,
+  pub extra_columns: Vec<ExtraColumn>,
+}
+
+/// The runtime used by the generated `check_consistency` method and test.
+#[derive(Default)]
+pub enum TestRuntime {
+  /// A blocking runner, with a regular `#[test]`.
+  Sync,
+  #[default]
+  Tokio,
+  TokioMultiThread,
+  AsyncStd,
+  Smol,
+}
+
+impl TestRuntime {
+  pub fn is_async(&self) -> bool {
+    !matches!(self, Self::Sync)
+  }
+}
+
+pub struct CodeMapping {
+  pub column: String,
+  pub type_path: TokenStream2,
+}
+
+impl Parse for CodeMapping {
+  fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+    let mut column: Option<String> = None;
+    let mut type_path: Option<Path> = None;
+    let mut is_default = false;
+
+    let punctuated_args = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+    let args_len = punctuated_args.len();
+
+    for arg in punctuated_args {
+      let ident = arg.path().require_ident()?;
+
+      if ident == "default" {
+        is_default = true;
+      } else if ident == "column" {
+        check_duplicate!(ident, column);
+
+        column = Some(extract_string_lit(&arg.require_name_value()?.value)?);
+      } else if ident == "sql_type" {
+        check_duplicate!(ident, type_path, "sql_type");
+
+        type_path = Some(extract_path(arg.require_name_value()?.clone().value)?);
+      } else {
+        return Err(spanned_error!(
+          ident,
+          format!("Unknown attribute `{ident}`. Expected one of: `default`, `column`, `sql_type`")
+        ));
+      }
+    }
+
+    if is_default && args_len != 1 {
+      return Err(error!(
+        input.span(),
+        "Cannot use other `code_mapping` attributes when using `default`"
+      ));
+    }
+
+    Ok(Self {
+      column: column.unwrap_or_else(|| "code".to_string()),
+      type_path: type_path.map_or_else(
+        || quote! { diesel::sql_types::Text },
+        |path| path.to_token_stream(),
+      ),
+    })
+  }
+}
+
+pub struct ExtraColumn {
+  pub name: Ident,
+  pub rust_type: TokenStream2,
+  pub load_type: TokenStream2,
+}
+
+impl Parse for ExtraColumn {
+  fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+    let name = input.parse::<Ident>()?;
+
+    input.parse::<Token![:]>()?;
+
+    let sql_type = input.parse::<Path>()?;
+
+    let type_ident = path_ident(&sql_type)?;
+
+    let rust_type = if type_ident == "Text" {
+      quote! { &'static str }
+    } else if type_ident == "Bool" {
+      quote! { bool }
+    } else if type_ident == "Float" {
+      quote! { f32 }
+    } else if type_ident == "Double" {
+      quote! { f64 }
+    } else if let Some((_, signed_type, _)) = INT_TYPES
+      .iter()
+      .find(|(sql_type, ..)| type_ident == sql_type)
+    {
+      let signed_type = format_ident!("{signed_type}");
+
+      quote! { #signed_type }
+    } else {
+      return Err(spanned_error!(
+        type_ident,
+        format!("Unsupported type `{type_ident}` for an extra column. Expected one of: `Text`, `Bool`, `Integer`, `BigInt`, `SmallInt`, `TinyInt`, `Float`, `Double`")
+      ));
+    };
+
+    let load_type = if type_ident == "Text" {
+      quote! { String }
+    } else {
+      rust_type.clone()
+    };
+
+    Ok(Self {
+      name,
+      rust_type,
+      load_type,
+    })
+  }
 }
 
 pub struct IdMapping {
   pub type_path: TokenStream2,
   pub rust_type: Ident,
+  pub explicit_type: bool,
+}
+
+// The sql types, along with their signed and unsigned rust counterparts
+const INT_TYPES: [(&str, &str, &str); 4] = [
+  ("Integer", "i32", "u32"),
+  ("BigInt", "i64", "u64"),
+  ("SmallInt", "i16", "u16"),
+  ("TinyInt", "i8", "u8"),
+];
+
+// The integer reprs that cannot be used for an id mapping
+const UNSUPPORTED_INT_REPRS: [&str; 4] = ["i128", "u128", "isize", "usize"];
+
+impl IdMapping {
+  /// Uses the integer type from the `#[repr(...)]` attribute, unless the `sql_type` was set explicitly.
+  ///
+  /// The other reprs (i.e. `C`) are ignored. The unsigned types are only supported by mysql, so they require setting the `sql_type` explicitly.
+  pub fn apply_repr(&mut self, attr: &Attribute) -> Result<(), Error> {
+    let reprs = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+    for repr in reprs.iter().filter_map(|meta| meta.path().get_ident()) {
+      if UNSUPPORTED_INT_REPRS.iter().any(|int_type| repr == int_type) {
+        return Err(spanned_error!(
+          repr,
+          format!("Unsupported repr `{repr}` for an id mapping. Expected one of: `i8`, `i16`, `i32`, `i64`, `u8`, `u16`, `u32`, `u64`")
+        ));
+      }
+
+      let Some((sql_type, signed, _)) = INT_TYPES
+        .iter()
+        .find(|(_, signed, unsigned)| repr == signed || repr == unsigned)
+      else {
+        continue;
+      };
+
+      if self.explicit_type {
+        if self.rust_type != *repr {
+          return Err(spanned_error!(
+            repr,
+            format!(
+              "The enum is `repr({repr})` but the `sql_type` of the id mapping is converted to `{}`",
+              self.rust_type
+            )
+          ));
+        }
+      } else if repr == signed {
+        let sql_type = format_ident!("{sql_type}");
+
+        self.type_path = quote! { diesel::sql_types::#sql_type };
+        self.rust_type = repr.clone();
+      } else {
+        return Err(spanned_error!(
+          repr,
+          format!("Only mysql supports unsigned integers, so the type of the ids cannot be inferred from `repr({repr})`. Set the `sql_type` of the id mapping explicitly (i.e. `diesel::sql_types::Unsigned<diesel::sql_types::{sql_type}>` for mysql)")
+        ));
+      }
+    }
+
+    Ok(())
+  }
 }
 
 impl Default for IdMapping {
@@ -30,30 +221,31 @@ impl Default for IdMapping {
     Self {
       type_path: quote! { diesel::sql_types::Integer },
       rust_type: format_ident!("i32"),
+      explicit_type: false,
     }
   }
 }
 
 struct SkippedRanges {
-  pub ranges: Vec<Range<i32>>,
+  pub ranges: Vec<Range<i128>>,
 }
 
 impl Parse for SkippedRanges {
   fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-    let mut ranges: Vec<Range<i32>> = Vec::new();
+    let mut ranges: Vec<Range<i128>> = Vec::new();
 
     let items = Punctuated::<Expr, Token![,]>::parse_terminated(input)?;
 
     for item in items {
       if let Expr::Range(range_expr) = &item {
         let start = if let Some(start_expr) = &range_expr.start {
-          extract_i32(start_expr)?
+          extract_int(start_expr)?
         } else {
           0
         };
 
         let end = if let Some(end_expr) = &range_expr.end {
-          extract_i32(end_expr)?
+          extract_int(end_expr)?
         } else {
           return Err(spanned_error!(
             range_expr,
@@ -69,7 +261,7 @@ impl Parse for SkippedRanges {
 
         ranges.push(start..final_end);
       } else if let Expr::Lit(lit) = &item && let Lit::Int(lit_int) = &lit.lit {
-        let num = lit_int.base10_parse::<i32>()?;
+        let num = lit_int.base10_parse::<i128>()?;
 
         ranges.push(num..num + 1);
       } else {
@@ -86,65 +278,105 @@ impl Parse for SkippedRanges {
   }
 }
 
+fn path_ident(type_path: &Path) -> Result<&Ident, Error> {
+  Ok(
+    &type_path
+      .segments
+      .last()
+      .ok_or_else(|| spanned_error!(type_path.clone(), "Invalid type path"))?
+      .ident,
+  )
+}
+
+fn id_rust_type(sql_type: &Type) -> Result<Ident, Error> {
+  let unknown_type_error = || {
+    spanned_error!(
+      sql_type,
+      "Unknown ID type. Only valid integer types from `diesel::sql_types` (optionally wrapped in `Unsigned<...>`) are accepted"
+    )
+  };
+
+  let Type::Path(type_path) = sql_type else {
+    return Err(unknown_type_error());
+  };
+
+  let mut type_ident = path_ident(&type_path.path)?;
+  let mut unsigned = false;
+
+  if type_ident == "Unsigned" {
+    unsigned = true;
+
+    let last_segment = type_path.path.segments.last().unwrap();
+
+    if let PathArguments::AngleBracketed(args) = &last_segment.arguments
+      && let Some(GenericArgument::Type(Type::Path(inner_path))) = args.args.first()
+    {
+      type_ident = path_ident(&inner_path.path)?;
+    } else {
+      return Err(unknown_type_error());
+    }
+  }
+
+  let (_, signed_type, unsigned_type) = INT_TYPES
+    .iter()
+    .find(|(sql_type, ..)| type_ident == sql_type)
+    .ok_or_else(unknown_type_error)?;
+
+  Ok(format_ident!(
+    "{}",
+    if unsigned { unsigned_type } else { signed_type }
+  ))
+}
+
 impl Parse for IdMapping {
   fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
     let mut rust_type: Option<Ident> = None;
     let mut int_type_path: Option<TokenStream2> = None;
+    let mut is_default = false;
+    let mut args_len = 0;
 
-    let punctuated_args = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+    // Parsing manually because types with generics such as `Unsigned<Integer>` are not valid expressions
+    while !input.is_empty() {
+      let ident = input.parse::<Ident>()?;
 
-    let args_len = punctuated_args.len();
-
-    for arg in punctuated_args {
-      let ident = arg.path().require_ident()?;
+      args_len += 1;
 
       if ident == "default" {
-        if args_len != 1 {
-          return Err(error!(
-            input.span(),
-            "Cannot use other `id_mapping` attributes when using `default`"
-          ));
-        } else {
-          return Ok(Self::default());
-        }
+        is_default = true;
       } else if ident == "sql_type" {
         check_duplicate!(ident, rust_type, "sql_type");
 
-        let value = arg.require_name_value()?.clone().value;
+        input.parse::<Token![=]>()?;
 
-        let type_path = extract_path(value)?;
+        let sql_type = input.parse::<Type>()?;
 
-        let type_ident = &type_path
-          .segments
-          .last()
-          .ok_or_else(|| spanned_error!(type_path.clone(), "Invalid type path"))?
-          .ident;
-
-        let type_target = if type_ident == "Integer" {
-          "i32"
-        } else if type_ident == "BigInt" {
-          "i64"
-        } else if type_ident == "SmallInt" {
-          "i16"
-        } else if type_ident == "TinyInt" {
-          "i8"
-        } else {
-          return Err(spanned_error!(
-            type_ident,
-            format!("Unknown ID type {type_ident}. Only valid integer types from `diesel::sql_types` are accepted")));
-        };
-
-        rust_type = Some(format_ident!("{type_target}"));
-        int_type_path = Some(type_path.to_token_stream());
+        rust_type = Some(id_rust_type(&sql_type)?);
+        int_type_path = Some(sql_type.to_token_stream());
       } else {
         return Err(spanned_error!(
-          ident,
+          &ident,
           format!("Unknown attribute `{ident}`. Expected one of: `default`, `sql_type`")
         ));
       }
+
+      if !input.is_empty() {
+        input.parse::<Token![,]>()?;
+      }
+    }
+
+    if is_default {
+      if args_len != 1 {
+        return Err(error!(
+          input.span(),
+          "Cannot use other `id_mapping` attributes when using `default`"
+        ));
+      } else {
+        return Ok(Self::default());
+      }
     }
 
     Ok(IdMapping {
+      explicit_type: int_type_path.is_some(),
       type_path: int_type_path.unwrap_or_else(|| quote! { diesel::sql_types::Integer }),
       rust_type: rust_type.unwrap_or_else(|| format_ident!("i32")),
     })
@@ -154,11 +386,12 @@ impl Parse for IdMapping {
 pub enum NameTypes {
   Text,
   Custom { name: String },
+  MysqlEnum,
 }
 
 impl NameTypes {
   pub fn is_custom(&self) -> bool {
-    matches!(self, Self::Custom { .. })
+    matches!(self, Self::Custom { .. } | Self::MysqlEnum)
   }
 }
 
@@ -180,6 +413,7 @@ impl Parse for NameMapping {
   fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
     let mut custom_type_path: Option<Path> = None;
     let mut custom_enum_name: Option<String> = None;
+    let mut mysql_enum: Option<bool> = None;
 
     let punctuated_args = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
 
@@ -209,15 +443,39 @@ impl Parse for NameMapping {
         let db_enum_name = extract_string_lit(&arg.require_name_value()?.value)?;
 
         custom_enum_name = Some(db_enum_name);
+      } else if ident == "mysql_enum" {
+        check_duplicate!(ident, mysql_enum);
+
+        arg.require_path_only()?;
+
+        mysql_enum = Some(true);
       } else {
         return Err(spanned_error!(
           ident,
-          format!("Unknown attribute `{ident}`. Expected one of: `default`, `path`, `name`")
+          format!(
+            "Unknown attribute `{ident}`. Expected one of: `default`, `path`, `name`, `mysql_enum`"
+          )
         ));
       }
     }
 
-    let db_type = if let Some(path) = &custom_type_path {
+    let db_type = if mysql_enum.is_some() {
+      if custom_type_path.is_none() {
+        return Err(error!(
+          input.span(),
+          "`mysql_enum` requires the `path` to the sql type of the column"
+        ));
+      }
+
+      if custom_enum_name.is_some() {
+        return Err(error!(
+          input.span(),
+          "`name` is only used for custom postgres types and cannot be used with `mysql_enum`"
+        ));
+      }
+
+      NameTypes::MysqlEnum
+    } else if let Some(path) = &custom_type_path {
       let db_name = if let Some(name) = custom_enum_name {
         name
       } else {
@@ -253,9 +511,11 @@ pub fn extract_string_lit(expr: &Expr) -> Result<String, Error> {
   }
 }
 
-pub fn extract_i32(expr: &Expr) -> Result<i32, Error> {
+pub fn extract_int(expr: &Expr) -> Result<i128, Error> {
   if let Expr::Lit(expr_lit) = expr && let Lit::Int(value) = &expr_lit.lit {
     Ok(value.base10_parse()?)
+  } else if let Expr::Unary(unary) = expr && let UnOp::Neg(_) = unary.op {
+    Ok(-extract_int(&unary.expr)?)
   } else {
     Err(spanned_error!(expr, "Expected an integer literal"))
   }
@@ -275,16 +535,23 @@ impl<'a> Parse for Attributes<'a> {
     let mut table_path: Option<Path> = None;
     let mut column: Option<String> = None;
     let mut conn: Option<Check> = None;
+    let mut runtime_check: Option<Path> = None;
+    let mut snapshot: Option<(Ident, String)> = None;
+    let mut migrations: Option<(Ident, String)> = None;
+    let mut test_runtime: Option<(Ident, TestRuntime)> = None;
     let mut case: Option<Case> = None;
     let mut name_mapping: Option<NameMapping> = None;
     let mut id_mapping: Option<IdMapping> = None;
+    let mut code_mapping: Option<CodeMapping> = None;
     let mut skip_test: Option<bool> = None;
-    let mut skip_ids: Option<Vec<Range<i32>>> = None;
+    let mut check_order: Option<Ident> = None;
+    let mut skip_ids: Option<Vec<Range<i128>>> = None;
+    let mut extra_columns: Option<Vec<ExtraColumn>> = None;
 
     let punctuated_args = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
 
     let attributes_error_msg =
-      "Expected one of: `table_name`, `table`, `column`, `conn`, `skip_check`, `skip_ids`, `skip_test`, `case`, `id_mapping`, `name_mapping`";
+      "Expected one of: `table_name`, `table`, `column`, `conn`, `runtime_check`, `snapshot`, `migrations`, `test_runtime`, `skip_check`, `skip_ids`, `skip_test`, `check_order`, `case`, `id_mapping`, `code_mapping`, `name_mapping`, `extra_columns`";
 
     for arg in punctuated_args {
       match arg {
@@ -297,6 +564,12 @@ impl<'a> Parse for Attributes<'a> {
             let ranges = list.parse_args::<SkippedRanges>()?;
 
             skip_ids = Some(ranges.ranges);
+          } else if ident == "extra_columns" {
+            check_duplicate!(ident, extra_columns);
+
+            let columns = list.parse_args_with(Punctuated::<ExtraColumn, Token![,]>::parse_terminated)?;
+
+            extra_columns = Some(columns.into_iter().collect());
           } else if ident == "name_mapping" {
             check_duplicate!(ident, name_mapping);
 
@@ -309,6 +582,10 @@ impl<'a> Parse for Attributes<'a> {
             let parse_result = syn::parse2::<IdMapping>(list.tokens)?;
 
             id_mapping = Some(parse_result);
+          } else if ident == "code_mapping" {
+            check_duplicate!(ident, code_mapping);
+
+            code_mapping = Some(list.parse_args::<CodeMapping>()?);
           } else {
             return Err(spanned_error!(
               ident,
@@ -331,6 +608,10 @@ impl<'a> Parse for Attributes<'a> {
             check_duplicate!(ident, skip_test);
 
             skip_test = Some(true);
+          } else if ident == "check_order" {
+            check_duplicate!(ident, check_order);
+
+            check_order = Some(ident.clone());
           } else {
             return Err(spanned_error!(
               ident,
@@ -377,6 +658,31 @@ impl<'a> Parse for Attributes<'a> {
             }
 
             conn = Some(Check::Conn(extract_path(value)?.to_token_stream()));
+          } else if ident == "runtime_check" {
+            check_duplicate!(ident, runtime_check);
+
+            runtime_check = Some(extract_path(value)?);
+          } else if ident == "snapshot" {
+            check_duplicate!(ident, snapshot);
+
+            snapshot = Some((ident.clone(), extract_string_lit(&value)?));
+          } else if ident == "migrations" {
+            check_duplicate!(ident, migrations);
+
+            migrations = Some((ident.clone(), extract_string_lit(&value)?));
+          } else if ident == "test_runtime" {
+            check_duplicate!(ident, test_runtime);
+
+            let runtime = match extract_string_lit(&value)?.as_str() {
+              "sync" => TestRuntime::Sync,
+              "tokio" => TestRuntime::Tokio,
+              "tokio_multi_thread" => TestRuntime::TokioMultiThread,
+              "async_std" => TestRuntime::AsyncStd,
+              "smol" => TestRuntime::Smol,
+              _ => return Err(spanned_error!(value, "Invalid value for `test_runtime`. Allowed values are: [ sync, tokio, tokio_multi_thread, async_std, smol ]"))
+            };
+
+            test_runtime = Some((ident.clone(), runtime));
           } else {
             return Err(spanned_error!(
               ident,
@@ -387,17 +693,49 @@ impl<'a> Parse for Attributes<'a> {
       };
     }
 
+    // The migrations already check the mapping, so they do not require a test runner
     let conn = if let Some(input) = conn {
       input
+    } else if migrations.is_some() {
+      Check::Skip
     } else {
       return Err(error!(
         input.span(),
-        "At least one between `conn` and `skip_check` must be present"
+        "At least one between `conn`, `skip_check` and `migrations` must be present"
       ));
     };
 
+    if let Some((ident, _)) = &snapshot && !matches!(conn, Check::Conn(_)) {
+      return Err(spanned_error!(ident, "`snapshot` can only be used with `conn`"));
+    }
+
+    if let Some((ident, _)) = &test_runtime && !matches!(conn, Check::Conn(_)) {
+      return Err(spanned_error!(ident, "`test_runtime` can only be used with `conn`"));
+    }
+
     let is_custom_type = name_mapping.as_ref().is_some_and(|m| m.db_type.is_custom());
 
+    if let Some(ident) = &check_order && !is_custom_type {
+      return Err(spanned_error!(
+        ident,
+        "`check_order` can only be used with custom postgres types or mysql enum columns"
+      ));
+    }
+
+    if is_custom_type && let Some(column) = extra_columns.as_ref().and_then(|columns| columns.first()) {
+      return Err(spanned_error!(
+        &column.name,
+        "`extra_columns` can only be used with lookup tables"
+      ));
+    }
+
+    if code_mapping.is_some() && (id_mapping.is_some() || is_custom_type) {
+      return Err(error!(
+        input.span(),
+        "`code_mapping` cannot be used together with `id_mapping` or with custom types"
+      ));
+    }
+
     let id_mapping = if is_custom_type { None } else { id_mapping };
 
     if table_name.is_none() && let Some(path) = &table_path {
@@ -413,11 +751,18 @@ impl<'a> Parse for Attributes<'a> {
       table_path,
       column,
       conn,
+      runtime_check: runtime_check.map(|path| path.to_token_stream()),
+      snapshot: snapshot.map(|(_, path)| path),
+      migrations,
+      test_runtime: test_runtime.map(|(_, runtime)| runtime).unwrap_or_default(),
       case: case.unwrap_or(Case::Snake),
       id_mapping,
+      code_mapping,
       name_mapping,
       skip_test: skip_test.unwrap_or_default(),
+      check_order: check_order.is_some(),
       skip_ranges: skip_ids.unwrap_or_default(),
+      extra_columns: extra_columns.unwrap_or_default(),
     })
   }
 }
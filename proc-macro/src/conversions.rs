@@ -1,13 +1,86 @@
 use proc_macro2::Span;
-use quote::{format_ident, quote};
+use quote::quote;
 use syn::{Ident, LitByteStr, LitInt};
 
 use crate::{traverse_enum, TokenStream2, VariantData};
 
+fn fallback_error(enum_name: &Ident, fallback: &Ident) -> TokenStream2 {
+  quote! {
+    diesel_enums::FallbackVariantError {
+      enum_name: stringify!(#enum_name),
+      variant: stringify!(#fallback),
+    }
+  }
+}
+
+// The arm used by the `ToSql` implementations, which cannot write the fallback variant
+fn fallback_to_sql_arm(enum_name: &Ident, fallback: Option<&Ident>) -> TokenStream2 {
+  fallback
+    .map(|fallback| {
+      let error = fallback_error(enum_name, fallback);
+
+      quote! { Self::#fallback => return Err(Box::new(#error)), }
+    })
+    .unwrap_or_default()
+}
+
+// The accessors of an enum with a fallback variant return an `Option`, since the fallback variant has no value of its own.
+// Returns the return type and the body of the accessor.
+pub fn variant_accessor<'a>(
+  fallback: Option<&Ident>,
+  return_type: &TokenStream2,
+  arms: impl Iterator<Item = (&'a Ident, TokenStream2)>,
+) -> (TokenStream2, TokenStream2) {
+  if let Some(fallback) = fallback {
+    let arms = arms.map(|(variant, value)| quote! { Self::#variant => Some(#value), });
+
+    (
+      quote! { Option<#return_type> },
+      quote! {
+        match self {
+          #(#arms)*
+          Self::#fallback => None,
+        }
+      },
+    )
+  } else {
+    let arms = arms.map(|(variant, value)| quote! { Self::#variant => #value, });
+
+    (
+      quote! { #return_type },
+      quote! {
+        match self {
+          #(#arms)*
+        }
+      },
+    )
+  }
+}
+
+fn unknown_value_arm(
+  enum_name: &Ident,
+  fallback: Option<&Ident>,
+  pattern: TokenStream2,
+  value: TokenStream2,
+) -> TokenStream2 {
+  // The value is not used by the fallback arm, so it's not bound to avoid unused variable warnings
+  if let Some(fallback) = fallback {
+    quote! { _ => Ok(Self::#fallback), }
+  } else {
+    quote! {
+      #pattern => Err(diesel_enums::UnknownVariant {
+        enum_name: stringify!(#enum_name),
+        value: #value,
+      }.into()),
+    }
+  }
+}
+
 pub fn enum_int_conversions(
   enum_name: &Ident,
   rust_type: &Ident,
   variants_data: &[VariantData],
+  fallback: Option<&Ident>,
 ) -> TokenStream2 {
   let mut into_int = TokenStream2::new();
 
@@ -18,7 +91,7 @@ pub fn enum_int_conversions(
     let variant_ident = &variant.ident;
 
     into_int.extend(quote! {
-      Self::#variant_ident => #id,
+      #enum_name::#variant_ident => #id,
     });
 
     from_int.extend(quote! {
@@ -26,25 +99,54 @@ pub fn enum_int_conversions(
     });
   }
 
+  let unknown_id = unknown_value_arm(
+    enum_name,
+    fallback,
+    quote! { x },
+    quote! { diesel_enums::UnknownValue::Id(i128::from(x)) },
+  );
+
+  // The fallback variant has no id, so the conversion into an integer can only be fallible
+  let into_int_impl = if let Some(fallback) = fallback {
+    let error = fallback_error(enum_name, fallback);
+
+    quote! {
+      impl TryFrom<#enum_name> for #rust_type {
+        type Error = diesel_enums::FallbackVariantError;
+
+        fn try_from(value: #enum_name) -> Result<Self, Self::Error> {
+          Ok(match value {
+            #into_int
+            #enum_name::#fallback => return Err(#error),
+          })
+        }
+      }
+    }
+  } else {
+    quote! {
+      impl Into<#rust_type> for #enum_name {
+        fn into(self) -> #rust_type {
+          match self {
+            #into_int
+          }
+        }
+      }
+    }
+  };
+
   quote! {
     impl TryFrom<#rust_type> for #enum_name {
-      type Error = String;
+      type Error = diesel_enums::UnknownVariant;
 
       fn try_from(value: #rust_type) -> Result<Self, Self::Error> {
         match value {
           #from_int
-          x => Err(format!("Unknown `{}` variant: {x}", stringify!(#enum_name))),
+          #unknown_id
         }
       }
     }
 
-    impl Into<#rust_type> for #enum_name {
-      fn into(self) -> #rust_type {
-        match self {
-          #into_int
-        }
-      }
-    }
+    #into_int_impl
   }
 }
 
@@ -53,13 +155,17 @@ pub fn sql_int_conversions(
   rust_type: &Ident,
   sql_type_path: &TokenStream2,
   variants_data: &[VariantData],
+  fallback: Option<&Ident>,
 ) -> TokenStream2 {
+  let fallback_arm = fallback_to_sql_arm(enum_name, fallback);
+
   let to_sql_conversion = traverse_enum(variants_data, |data| {
     let variant = &data.ident;
     let id = LitInt::new(&format!("{}{}", data.id, rust_type), Span::call_site());
 
+    // Negative literals must be wrapped to be used as the receiver
     quote! {
-      Self::#variant => #id.to_sql(out),
+      Self::#variant => (#id).to_sql(out),
     }
   });
 
@@ -84,22 +190,29 @@ pub fn sql_int_conversions(
       fn to_sql<'b>(&'b self, out: &mut diesel::serialize::Output<'b, '_, DB>) -> diesel::serialize::Result {
         match self {
           #to_sql_conversion
+          #fallback_arm
         }
       }
     }
   }
 }
 
-pub fn postgres_enum_conversions(
+pub fn custom_type_conversions(
   enum_name: &Ident,
   sql_type_path: &TokenStream2,
+  backend: &TokenStream2,
   variants_data: &[VariantData],
+  fallback: Option<&Ident>,
 ) -> TokenStream2 {
   let mut conversion_to_bytes = TokenStream2::new();
   let mut conversion_from_bytes = TokenStream2::new();
 
   for data in variants_data {
     let db_name_bytes = LitByteStr::new(data.db_name.as_bytes(), Span::call_site());
+    let aliases_bytes = data
+      .aliases
+      .iter()
+      .map(|alias| LitByteStr::new(alias.as_bytes(), Span::call_site()));
     let variant_ident = &data.ident;
 
     conversion_to_bytes.extend(quote! {
@@ -107,27 +220,36 @@ pub fn postgres_enum_conversions(
     });
 
     conversion_from_bytes.extend(quote! {
-      #db_name_bytes => Ok(Self::#variant_ident),
+      #db_name_bytes #(| #aliases_bytes)* => Ok(Self::#variant_ident),
     });
   }
 
+  let unknown_name = unknown_value_arm(
+    enum_name,
+    fallback,
+    quote! { unknown },
+    quote! { diesel_enums::UnknownValue::Name(String::from_utf8_lossy(unknown).into_owned()) },
+  );
+  let fallback_arm = fallback_to_sql_arm(enum_name, fallback);
+
   quote! {
-    impl diesel::deserialize::FromSql<#sql_type_path, diesel::pg::Pg> for #enum_name
+    impl diesel::deserialize::FromSql<#sql_type_path, #backend> for #enum_name
     {
-      fn from_sql(bytes: diesel::pg::PgValue<'_>) -> diesel::deserialize::Result<Self> {
+      fn from_sql(bytes: <#backend as diesel::backend::Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
         match bytes.as_bytes() {
           #conversion_from_bytes
-          unknown => Err(Box::from(format!("Unknown `{}` variant: {}", stringify!(#enum_name), String::from_utf8_lossy(unknown)))),
+          #unknown_name
         }
       }
     }
 
-    impl diesel::serialize::ToSql<#sql_type_path, diesel::pg::Pg> for #enum_name
+    impl diesel::serialize::ToSql<#sql_type_path, #backend> for #enum_name
     {
-      fn to_sql<'b>(&'b self, out: &mut diesel::serialize::Output<'b, '_, diesel::pg::Pg>) -> diesel::serialize::Result {
+      fn to_sql<'b>(&'b self, out: &mut diesel::serialize::Output<'b, '_, #backend>) -> diesel::serialize::Result {
         use std::io::Write;
         match *self {
           #conversion_to_bytes
+          #fallback_arm
         };
         Ok(diesel::serialize::IsNull::No)
       }
@@ -135,44 +257,128 @@ pub fn postgres_enum_conversions(
   }
 }
 
-pub fn to_from_str_conversions(enum_name: &Ident, variants_data: &[VariantData]) -> TokenStream2 {
-  let mut conversion_to_str = TokenStream2::new();
+pub fn to_from_str_conversions(
+  enum_name: &Ident,
+  variants_data: &[VariantData],
+  fallback: Option<&Ident>,
+) -> TokenStream2 {
   let mut conversion_from_str = TokenStream2::new();
 
   for data in variants_data {
     let db_name = &data.db_name;
+    let aliases = &data.aliases;
     let variant_ident = &data.ident;
 
-    conversion_to_str.extend(quote! {
-      Self::#variant_ident => #db_name,
-    });
-
     conversion_from_str.extend(quote! {
-      #db_name => Ok(Self::#variant_ident),
+      #db_name #(| #aliases)* => Ok(Self::#variant_ident),
     });
   }
 
+  let unknown_name = unknown_value_arm(
+    enum_name,
+    fallback,
+    quote! { _ },
+    quote! { diesel_enums::UnknownValue::Name(name.to_string()) },
+  );
+  let (return_type, to_str_body) = variant_accessor(
+    fallback,
+    &quote! { &'static str },
+    variants_data.iter().map(|data| {
+      let db_name = &data.db_name;
+
+      (&data.ident, quote! { #db_name })
+    }),
+  );
+
   quote! {
     impl #enum_name {
       /// Returns the variant's corresponding name in the database source.
-      pub fn db_name(&self) -> &'static str {
-        match self {
-          #conversion_to_str
-        }
+      pub fn db_name(&self) -> #return_type {
+        #to_str_body
       }
 
-      /// Returns the enum variant corresponding to a given name, if there is one.
-      pub fn from_db_name(name: &str) -> Result<Self, String> {
+      /// Returns the enum variant corresponding to a given name (or one of its aliases), if there is one.
+      pub fn from_db_name(name: &str) -> Result<Self, diesel_enums::UnknownVariant> {
         match name {
           #conversion_from_str
-          _ => Err(format!("No matching {} variant found for `{name}`", stringify!(#enum_name)))
+          #unknown_name
         }
       }
     }
   }
 }
 
-pub fn sql_string_conversions(enum_name: &Ident, sql_type_path: &TokenStream2) -> TokenStream2 {
+pub fn code_conversions(
+  enum_name: &Ident,
+  variants_data: &[VariantData],
+  fallback: Option<&Ident>,
+) -> TokenStream2 {
+  let mut conversion_from_code = TokenStream2::new();
+
+  for data in variants_data {
+    let code = data.code.as_deref().unwrap_or_default();
+    let variant_ident = &data.ident;
+
+    conversion_from_code.extend(quote! {
+      #code => Ok(Self::#variant_ident),
+    });
+  }
+
+  let unknown_code = unknown_value_arm(
+    enum_name,
+    fallback,
+    quote! { _ },
+    quote! { diesel_enums::UnknownValue::Code(code.to_string()) },
+  );
+  let (return_type, to_code_body) = variant_accessor(
+    fallback,
+    &quote! { &'static str },
+    variants_data.iter().map(|data| {
+      let code = data.code.as_deref().unwrap_or_default();
+
+      (&data.ident, quote! { #code })
+    }),
+  );
+
+  quote! {
+    impl #enum_name {
+      /// Returns the variant's corresponding code in the database source.
+      pub fn code(&self) -> #return_type {
+        #to_code_body
+      }
+
+      /// Returns the enum variant corresponding to a given code, if there is one.
+      pub fn from_code(code: &str) -> Result<Self, diesel_enums::UnknownVariant> {
+        match code {
+          #conversion_from_code
+          #unknown_code
+        }
+      }
+    }
+  }
+}
+
+pub fn sql_string_conversions(
+  enum_name: &Ident,
+  sql_type_path: &TokenStream2,
+  to_str_method: &Ident,
+  from_str_method: &Ident,
+  fallback: Option<&Ident>,
+) -> TokenStream2 {
+  // With a fallback variant, the accessor returns `None` for it
+  let to_sql_body = if let Some(fallback) = fallback {
+    let error = fallback_error(enum_name, fallback);
+
+    quote! {
+      match self.#to_str_method() {
+        Some(value) => value.to_sql(out),
+        None => Err(Box::new(#error)),
+      }
+    }
+  } else {
+    quote! { self.#to_str_method().to_sql(out) }
+  };
+
   quote! {
     impl<DB> diesel::deserialize::FromSql<#sql_type_path, DB> for #enum_name
     where
@@ -182,7 +388,7 @@ pub fn sql_string_conversions(enum_name: &Ident, sql_type_path: &TokenStream2) -
       fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
        let value = <String as diesel::deserialize::FromSql<#sql_type_path, DB>>::from_sql(bytes)?;
 
-        Self::from_db_name(&value).map_err(Box::from)
+        Self::#from_str_method(&value).map_err(Box::from)
       }
     }
 
@@ -192,14 +398,26 @@ pub fn sql_string_conversions(enum_name: &Ident, sql_type_path: &TokenStream2) -
       str: diesel::serialize::ToSql<#sql_type_path, DB>,
     {
       fn to_sql<'b>(&'b self, out: &mut diesel::serialize::Output<'b, '_, DB>) -> diesel::serialize::Result {
-        self.db_name().to_sql(out)
+        #to_sql_body
       }
     }
   }
 }
 
-pub fn enum_to_enum_conversion(enum_name: &Ident, variants_data: &[VariantData]) -> TokenStream2 {
-  let id_enum = format_ident!("{enum_name}Id");
+pub fn enum_to_enum_conversion(
+  enum_name: &Ident,
+  id_enum: &Ident,
+  variants_data: &[VariantData],
+  fallback: Option<&Ident>,
+) -> TokenStream2 {
+  let (from_text_fallback, from_id_fallback) = fallback
+    .map(|fallback| {
+      (
+        quote! { #enum_name::#fallback => #id_enum::#fallback, },
+        quote! { #id_enum::#fallback => #enum_name::#fallback, },
+      )
+    })
+    .unzip();
 
   let from_text_enum = traverse_enum(variants_data, |variant| {
     let variant_ident = &variant.ident;
@@ -222,6 +440,7 @@ pub fn enum_to_enum_conversion(enum_name: &Ident, variants_data: &[VariantData])
       fn from(value: #enum_name) -> Self {
         match value {
           #from_text_enum
+          #from_text_fallback
         }
       }
     }
@@ -230,6 +449,7 @@ pub fn enum_to_enum_conversion(enum_name: &Ident, variants_data: &[VariantData])
       fn from(value: #id_enum) -> Self {
         match value {
           #from_id_enum
+          #from_id_fallback
         }
       }
     }
@@ -0,0 +1,131 @@
+use quote::quote;
+use syn::Ident;
+
+use crate::{
+  attributes::ExtraColumn, conversions::variant_accessor, test_generation::table_query_error,
+  TokenStream2, VariantData,
+};
+
+pub fn extra_column_accessors(
+  enum_name: &Ident,
+  extra_columns: &[ExtraColumn],
+  variants_data: &[VariantData],
+  fallback: Option<&Ident>,
+) -> TokenStream2 {
+  let accessors = extra_columns.iter().enumerate().map(|(i, column)| {
+    let column_name = &column.name;
+    let rust_type = &column.rust_type;
+    let doc = format!(" Returns the value of the `{column_name}` column for this variant.");
+
+    let (return_type, body) = variant_accessor(
+      fallback,
+      &quote! { #rust_type },
+      variants_data.iter().map(|data| {
+        let value = &data.extra_values[i];
+
+        (&data.ident, quote! { #value })
+      }),
+    );
+
+    quote! {
+      #[doc = #doc]
+      pub fn #column_name(&self) -> #return_type {
+        #body
+      }
+    }
+  });
+
+  quote! {
+    impl #enum_name {
+      #(#accessors)*
+    }
+  }
+}
+
+pub fn extra_columns_check(
+  table_path: &TokenStream2,
+  table_name: &str,
+  column_name: &Ident,
+  source_type: &TokenStream2,
+  extra_columns: &[ExtraColumn],
+  variants_data: &[VariantData],
+) -> TokenStream2 {
+  if extra_columns.is_empty() {
+    return quote! {
+      let value_mismatches: Vec<diesel_enums::ErrorKind> = Vec::new();
+    };
+  }
+
+  let columns: Vec<&Ident> = extra_columns.iter().map(|column| &column.name).collect();
+  let load_types = extra_columns.iter().map(|column| &column.load_type);
+
+  let query_sql = format!(
+    "SELECT {column_name}, {} FROM {table_name}",
+    columns
+      .iter()
+      .map(|column| column.to_string())
+      .collect::<Vec<String>>()
+      .join(", ")
+  );
+
+  let query_error = table_query_error(
+    table_name,
+    &std::iter::once(column_name.to_string())
+      .chain(columns.iter().map(|column| column.to_string()))
+      .collect::<Vec<String>>(),
+    source_type,
+    &query_sql,
+  );
+
+  let variant_arms = variants_data.iter().map(|data| {
+    let db_name = &data.db_name;
+
+    let comparisons = extra_columns
+      .iter()
+      .zip(&data.extra_values)
+      .map(|(column, value)| {
+        let column_name = &column.name;
+
+        quote! {
+          if #column_name != #value {
+            value_mismatches.push(diesel_enums::ErrorKind::ValueMismatch {
+              variant: #db_name.to_string(),
+              column: stringify!(#column_name).to_string(),
+              expected: format!("{:?}", #value),
+              found: format!("{:?}", #column_name),
+            });
+          }
+        }
+      });
+
+    quote! {
+      #db_name => {
+        #(#comparisons)*
+      }
+    }
+  });
+
+  let columns_tuple = quote! { (#(#columns,)*) };
+
+  quote! {
+    let value_mismatches: Vec<diesel_enums::ErrorKind> = {
+      let mut value_mismatches = Vec::new();
+
+      let rows: Vec<(String, (#(#load_types,)*))> = #table_path::table
+        .select((#table_path::#column_name, (#(#table_path::#columns,)*)))
+        .load(conn)
+        .map_err(#query_error)?;
+
+      for (name, #columns_tuple) in rows {
+        let canonical_name = aliases.get(name.as_str()).copied().unwrap_or(name.as_str());
+
+        match canonical_name {
+          #(#variant_arms)*
+          _ => {}
+        }
+      }
+
+      value_mismatches
+    };
+  }
+}
@@ -0,0 +1,68 @@
+use proc_macro2::Span;
+use quote::quote;
+use syn::{Ident, LitInt};
+
+use crate::{TokenStream2, VariantData};
+
+pub fn variants_iteration(enum_name: &Ident, variants_data: &[VariantData]) -> TokenStream2 {
+  let variants = variants_data.iter().map(|data| &data.ident);
+  let count = variants_data.len();
+
+  quote! {
+    impl #enum_name {
+      /// All the variants of the enum that are mapped to the database, in the order in which they are defined.
+      ///
+      /// The fallback variant (marked with `#[db_mapping(other)]`), if there is one, is not included.
+      pub const VARIANTS: &'static [Self] = &[ #(Self::#variants),* ];
+
+      /// The number of variants in [`Self::VARIANTS`] (so it does not count the fallback variant).
+      pub const COUNT: usize = #count;
+
+      /// Returns an iterator over all the variants of the enum, in the order in which they are defined.
+      pub fn iter() -> std::iter::Copied<std::slice::Iter<'static, Self>> {
+        Self::VARIANTS.iter().copied()
+      }
+    }
+  }
+}
+
+pub fn db_names_const(enum_name: &Ident, variants_data: &[VariantData]) -> TokenStream2 {
+  let db_names = variants_data.iter().map(|data| &data.db_name);
+
+  quote! {
+    impl #enum_name {
+      /// The names of the variants in the database source, in the same order as [`Self::VARIANTS`].
+      pub const DB_NAMES: &'static [&'static str] = &[ #(#db_names),* ];
+    }
+  }
+}
+
+pub fn ids_const(
+  enum_name: &Ident,
+  rust_type: &Ident,
+  variants_data: &[VariantData],
+) -> TokenStream2 {
+  let ids = variants_data
+    .iter()
+    .map(|data| LitInt::new(&format!("{}{}", data.id, rust_type), Span::call_site()));
+
+  quote! {
+    impl #enum_name {
+      /// The ids of the variants in the database source, in the same order as [`Self::VARIANTS`].
+      pub const IDS: &'static [#rust_type] = &[ #(#ids),* ];
+    }
+  }
+}
+
+pub fn codes_const(enum_name: &Ident, variants_data: &[VariantData]) -> TokenStream2 {
+  let codes = variants_data
+    .iter()
+    .map(|data| data.code.as_deref().unwrap_or_default());
+
+  quote! {
+    impl #enum_name {
+      /// The codes of the variants in the database source, in the same order as [`Self::VARIANTS`].
+      pub const CODES: &'static [&'static str] = &[ #(#codes),* ];
+    }
+  }
+}
@@ -4,22 +4,30 @@
 pub(crate) mod macros;
 pub(crate) mod attributes;
 pub(crate) mod conversions;
+pub(crate) mod extra_columns;
+pub(crate) mod iteration;
+pub(crate) mod migrations;
 pub(crate) mod process_variants;
+pub(crate) mod sql_generation;
 pub(crate) mod test_generation;
 
 use convert_case::{Case, Casing};
 use proc_macro::TokenStream;
 pub(crate) use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
-use syn::{parse_macro_input, Error, ItemEnum};
+use syn::{parse_macro_input, Error, Ident, ItemEnum};
 
 use crate::{
-  attributes::{Attributes, IdMapping, NameMapping},
+  attributes::{Attributes, NameMapping, NameTypes},
   conversions::{
-    enum_int_conversions, enum_to_enum_conversion, postgres_enum_conversions, sql_int_conversions,
-    sql_string_conversions, to_from_str_conversions,
+    code_conversions, custom_type_conversions, enum_int_conversions, enum_to_enum_conversion,
+    sql_int_conversions, sql_string_conversions, to_from_str_conversions,
   },
+  extra_columns::extra_column_accessors,
+  iteration::{codes_const, db_names_const, ids_const, variants_iteration},
+  migrations::{check_migrations, MigrationsSource},
   process_variants::{process_variants, VariantData},
+  sql_generation::{create_type_sql, seed_sql, sync_to_db},
   test_generation::{test_with_id, test_without_id},
 };
 
@@ -28,6 +36,24 @@ enum Check {
   Skip,
 }
 
+/// The column used to identify the rows of a lookup table, other than the name.
+enum KeyColumn<'a> {
+  /// An integer id, with its rust type.
+  Id(&'a Ident),
+  /// A string code, with the name of its column.
+  Code(&'a str),
+}
+
+impl KeyColumn<'_> {
+  /// The suffix of the companion enum that is created when the key is combined with a name mapping.
+  fn suffix(&self) -> &'static str {
+    match self {
+      Self::Id(_) => "Id",
+      Self::Code(_) => "Code",
+    }
+  }
+}
+
 fn traverse_enum<T>(variants: &[VariantData], action: T) -> TokenStream2
 where
   T: Fn(&VariantData) -> TokenStream2,
@@ -52,18 +78,40 @@ pub fn diesel_enum(attrs: TokenStream, input: TokenStream) -> TokenStream {
   let Attributes {
     table_path,
     skip_test,
+    check_order,
     table_name,
     column,
     conn,
+    runtime_check,
+    snapshot,
+    migrations,
+    test_runtime,
     case,
     name_mapping,
-    id_mapping,
+    mut id_mapping,
+    code_mapping,
     skip_ranges,
+    extra_columns,
   } = parse_macro_input!(attrs as Attributes);
 
   let ast = parse_macro_input!(input as ItemEnum);
 
-  let variants_data = match process_variants(&ast.variants, case, &skip_ranges) {
+  if let Some(mapping) = &mut id_mapping {
+    for attr in &ast.attrs {
+      if attr.path().is_ident("repr") && let Err(e) = mapping.apply_repr(attr) {
+        return e.to_compile_error().into();
+      }
+    }
+  }
+
+  let (variants_data, fallback) = match process_variants(
+    &ast.variants,
+    case,
+    &skip_ranges,
+    id_mapping.as_ref().map(|mapping| &mapping.rust_type),
+    &extra_columns,
+    code_mapping.is_some(),
+  ) {
     Ok(data) => data,
     Err(e) => return e.to_compile_error().into(),
   };
@@ -72,6 +120,7 @@ pub fn diesel_enum(attrs: TokenStream, input: TokenStream) -> TokenStream {
   let enum_name_str = enum_name.to_string();
 
   let table_name = table_name.unwrap_or_else(|| enum_name_str.to_case(Case::Snake));
+  let has_table_path = table_path.is_some();
   let table_path = table_path.unwrap_or_else(|| {
     let table_name_ident = format_ident!("{table_name}");
     quote! { crate::schema::#table_name_ident }
@@ -80,17 +129,85 @@ pub fn diesel_enum(attrs: TokenStream, input: TokenStream) -> TokenStream {
 
   let mut enum_impls = TokenStream2::new();
 
-  if id_mapping.is_none() && name_mapping.is_none() {
+  let key_mapping = match (&id_mapping, &code_mapping) {
+    (Some(mapping), _) => Some((KeyColumn::Id(&mapping.rust_type), &mapping.type_path)),
+    (_, Some(mapping)) => Some((KeyColumn::Code(&mapping.column), &mapping.type_path)),
+    _ => None,
+  };
+
+  if key_mapping.is_none() && name_mapping.is_none() {
     return Error::new_spanned(
       orig_input,
-      "At least one between `id_mapping` and `name_mapping` must be set",
+      "At least one between `id_mapping`, `code_mapping` and `name_mapping` must be set",
     )
     .to_compile_error()
     .into();
   }
 
-  let is_double_mapping =
-    name_mapping.is_some() && id_mapping.is_some() && matches!(conn, Check::Conn(_));
+  let conn_callback = match &conn {
+    Check::Conn(connection_func) => Some(connection_func),
+    Check::Skip => None,
+  };
+
+  let has_checks = conn_callback.is_some() || runtime_check.is_some();
+
+  // The table is only known to exist in the schema if it's used by the checks or set explicitly
+  let is_table_backed = has_checks || has_table_path;
+
+  // The companion enum only depends on the mappings, so that enabling or disabling the checks does not change the generated types
+  let is_double_mapping = name_mapping.is_some() && key_mapping.is_some();
+
+  if let Some((attr_ident, migrations_path)) = &migrations {
+    let migrations_check = MigrationsSource::new(
+      name_mapping.as_ref().map(|mapping| &mapping.db_type),
+      check_order,
+      &table_name,
+      column_name,
+      key_mapping.as_ref().map(|(key_column, _)| key_column),
+    )
+    .map_err(|e| spanned_error!(attr_ident, e))
+    .and_then(|source| {
+      check_migrations(
+        enum_name,
+        attr_ident,
+        migrations_path,
+        &source,
+        &variants_data,
+        fallback.as_ref(),
+      )
+    });
+
+    match migrations_check {
+      Ok(tokens) => enum_impls.extend(tokens),
+      Err(e) => return e.to_compile_error().into(),
+    };
+  }
+
+  enum_impls.extend(variants_iteration(enum_name, &variants_data));
+  enum_impls.extend(db_names_const(enum_name, &variants_data));
+  enum_impls.extend(extra_column_accessors(
+    enum_name,
+    &extra_columns,
+    &variants_data,
+    fallback.as_ref(),
+  ));
+
+  match name_mapping.as_ref().map(|mapping| &mapping.db_type) {
+    Some(NameTypes::Custom { name }) => {
+      enum_impls.extend(create_type_sql(enum_name, name, &variants_data));
+    }
+    Some(NameTypes::MysqlEnum) => {}
+    _ => {
+      enum_impls.extend(seed_sql(
+        enum_name,
+        &table_name,
+        column_name,
+        &variants_data,
+        &extra_columns,
+        key_mapping.as_ref().map(|(key_column, _)| key_column),
+      ));
+    }
+  };
 
   if let Some(NameMapping {
     path: sql_type_path,
@@ -103,84 +220,169 @@ pub fn diesel_enum(attrs: TokenStream, input: TokenStream) -> TokenStream {
       #orig_input
     });
 
-    let is_custom_type = db_type.is_custom();
-
-    let to_from_str_conversions = to_from_str_conversions(enum_name, &variants_data);
+    let to_from_str_conversions =
+      to_from_str_conversions(enum_name, &variants_data, fallback.as_ref());
 
     enum_impls.extend(to_from_str_conversions);
 
-    let sql_conversions = if is_custom_type {
-      postgres_enum_conversions(enum_name, sql_type_path, &variants_data)
-    } else {
-      sql_string_conversions(enum_name, sql_type_path)
+    let sql_conversions = match db_type {
+      NameTypes::Text => sql_string_conversions(
+        enum_name,
+        sql_type_path,
+        &format_ident!("db_name"),
+        &format_ident!("from_db_name"),
+        fallback.as_ref(),
+      ),
+      NameTypes::Custom { .. } => custom_type_conversions(
+        enum_name,
+        sql_type_path,
+        &quote! { diesel::pg::Pg },
+        &variants_data,
+        fallback.as_ref(),
+      ),
+      NameTypes::MysqlEnum => custom_type_conversions(
+        enum_name,
+        sql_type_path,
+        &quote! { diesel::mysql::Mysql },
+        &variants_data,
+        fallback.as_ref(),
+      ),
     };
 
     enum_impls.extend(sql_conversions);
 
-    if !is_double_mapping && let Check::Conn(connection_func) = &conn {
-      let test_impl =  {
-        test_without_id(
-          enum_name,
-          &enum_name_str,
-          &table_path,
-          &table_name,
-          column_name,
-          db_type,
-          connection_func,
-          &variants_data,
-          skip_test,
-        )
-      };
+    if !is_double_mapping && has_checks {
+      let test_impl = test_without_id(
+        enum_name,
+        &enum_name_str,
+        &table_path,
+        &table_name,
+        column_name,
+        db_type,
+        conn_callback,
+        runtime_check.as_ref(),
+        snapshot.as_deref(),
+        &test_runtime,
+        &variants_data,
+        skip_test,
+        check_order,
+        fallback.as_ref(),
+        &extra_columns,
+      );
 
       enum_impls.extend(test_impl);
     }
+
+    // With a key mapping, the lookup table is synchronized along with the keys
+    if let NameTypes::Text = db_type && key_mapping.is_none() && is_table_backed {
+      enum_impls.extend(sync_to_db(
+        enum_name,
+        &table_path,
+        &table_name,
+        column_name,
+        None,
+        &variants_data,
+        &extra_columns,
+      ));
+    }
   }
 
-  if let Some(IdMapping {
-    type_path: sql_type_path,
-    rust_type,
-  }) = id_mapping
-  {
+  if let Some((key_column, sql_type_path)) = &key_mapping {
     let original_enum_name = enum_name;
 
     let target_enum_name = if is_double_mapping {
-      format_ident!("{enum_name}Id")
+      format_ident!("{enum_name}{}", key_column.suffix())
     } else {
       original_enum_name.clone()
     };
 
     let target_enum_str = target_enum_name.to_string();
 
-    let int_to_from_sql = sql_int_conversions(
-      &target_enum_name,
-      &rust_type,
-      &sql_type_path,
-      &variants_data,
-    );
+    match key_column {
+      KeyColumn::Id(rust_type) => {
+        let int_to_from_sql = sql_int_conversions(
+          &target_enum_name,
+          rust_type,
+          sql_type_path,
+          &variants_data,
+          fallback.as_ref(),
+        );
+
+        enum_impls.extend(int_to_from_sql);
+
+        let int_conversion = enum_int_conversions(
+          &target_enum_name,
+          rust_type,
+          &variants_data,
+          fallback.as_ref(),
+        );
 
-    enum_impls.extend(int_to_from_sql);
+        enum_impls.extend(int_conversion);
 
-    let int_conversion = enum_int_conversions(&target_enum_name, &rust_type, &variants_data);
+        enum_impls.extend(ids_const(&target_enum_name, rust_type, &variants_data));
+      }
+      KeyColumn::Code(_) => {
+        enum_impls.extend(code_conversions(
+          &target_enum_name,
+          &variants_data,
+          fallback.as_ref(),
+        ));
+
+        enum_impls.extend(sql_string_conversions(
+          &target_enum_name,
+          sql_type_path,
+          &format_ident!("code"),
+          &format_ident!("from_code"),
+          fallback.as_ref(),
+        ));
+
+        enum_impls.extend(codes_const(&target_enum_name, &variants_data));
+      }
+    };
 
-    enum_impls.extend(int_conversion);
+    if is_double_mapping {
+      enum_impls.extend(variants_iteration(&target_enum_name, &variants_data));
+      enum_impls.extend(extra_column_accessors(
+        &target_enum_name,
+        &extra_columns,
+        &variants_data,
+        fallback.as_ref(),
+      ));
+    }
 
-    if let Check::Conn(connection_func) = &conn {
+    if has_checks {
       let test_impl = test_with_id(
         original_enum_name,
         &target_enum_str,
         &table_path,
         &table_name,
         column_name,
-        &rust_type,
-        connection_func,
+        key_column,
+        conn_callback,
+        runtime_check.as_ref(),
+        snapshot.as_deref(),
+        &test_runtime,
         &variants_data,
         skip_test,
-        is_double_mapping,
+        fallback.as_ref(),
+        &extra_columns,
       );
 
       enum_impls.extend(test_impl);
     }
 
+    if is_table_backed {
+      enum_impls.extend(sync_to_db(
+        original_enum_name,
+        &table_path,
+        &table_name,
+        column_name,
+        Some(key_column),
+        &variants_data,
+        &extra_columns,
+      ));
+    }
+
     if !is_double_mapping {
       enum_impls.extend(quote! {
         #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, diesel_enums::MappedEnum, diesel::deserialize::FromSqlRow, diesel::expression::AsExpression)]
@@ -188,7 +390,12 @@ pub fn diesel_enum(attrs: TokenStream, input: TokenStream) -> TokenStream {
         #orig_input
       });
     } else {
-      let enum_to_enum_conversion_tokens = enum_to_enum_conversion(enum_name, &variants_data);
+      let enum_to_enum_conversion_tokens = enum_to_enum_conversion(
+        enum_name,
+        &target_enum_name,
+        &variants_data,
+        fallback.as_ref(),
+      );
 
       let mut enum_copy = ast.clone();
 
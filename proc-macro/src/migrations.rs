@@ -0,0 +1,1130 @@
+//! Reconstructs the values of a database source from the `up.sql` files of a migrations directory, so that the mapping can be checked at compile time.
+//!
+//! Only the statements that can affect the values of a custom type or of a lookup table are interpreted, and everything else is ignored.
+
+use std::{
+  collections::{HashMap, HashSet},
+  fs,
+  path::{Path, PathBuf},
+};
+
+use quote::quote;
+use syn::{Error, Ident};
+
+use crate::{attributes::NameTypes, KeyColumn, TokenStream2, VariantData};
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+  // An unquoted identifier or keyword
+  Word(String),
+  // A quoted identifier
+  Quoted(String),
+  Str(String),
+  Number(String),
+  Punct(char),
+}
+
+impl Token {
+  fn is_keyword(&self, keyword: &str) -> bool {
+    matches!(self, Self::Word(word) if word.eq_ignore_ascii_case(keyword))
+  }
+
+  fn identifier(&self) -> Option<&str> {
+    match self {
+      Self::Word(name) | Self::Quoted(name) => Some(name),
+      _ => None,
+    }
+  }
+}
+
+fn tokenize(sql: &str) -> Result<Vec<Token>, String> {
+  let chars: Vec<char> = sql.chars().collect();
+  let mut tokens: Vec<Token> = Vec::new();
+  let mut i = 0;
+
+  // Reads a quoted sequence, where the quote character is escaped by doubling it
+  let read_quoted = |i: &mut usize, quote: char| -> Result<String, String> {
+    let mut content = String::new();
+    *i += 1;
+
+    loop {
+      match chars.get(*i) {
+        Some(c) if *c == quote => {
+          if chars.get(*i + 1) == Some(&quote) {
+            content.push(quote);
+            *i += 2;
+          } else {
+            *i += 1;
+            return Ok(content);
+          }
+        }
+        Some(c) => {
+          content.push(*c);
+          *i += 1;
+        }
+        None => return Err(format!("Unterminated quoted sequence `{quote}{content}`")),
+      }
+    }
+  };
+
+  while let Some(&c) = chars.get(i) {
+    let next = chars.get(i + 1).copied();
+
+    if c.is_whitespace() {
+      i += 1;
+    } else if c == '-' && next == Some('-') {
+      while chars.get(i).is_some_and(|c| *c != '\n') {
+        i += 1;
+      }
+    } else if c == '/' && next == Some('*') {
+      i += 2;
+
+      while chars.get(i).is_some() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+        i += 1;
+      }
+
+      i += 2;
+    } else if c == '\'' {
+      tokens.push(Token::Str(read_quoted(&mut i, '\'')?));
+    } else if c == '"' || c == '`' {
+      tokens.push(Token::Quoted(read_quoted(&mut i, c)?));
+    } else if c == '$' {
+      // Dollar-quoted strings, which are mostly used in the bodies of functions
+      let tag_end = chars[i + 1..]
+        .iter()
+        .position(|c| !(c.is_alphanumeric() || *c == '_'))
+        .map(|pos| i + 1 + pos);
+
+      match tag_end {
+        Some(tag_end) if chars[tag_end] == '$' => {
+          let tag: String = chars[i..=tag_end].iter().collect();
+          let rest: String = chars[tag_end + 1..].iter().collect();
+
+          let content_len = rest
+            .find(&tag)
+            .ok_or_else(|| format!("Unterminated dollar-quoted string `{tag}`"))?;
+
+          let content: String = rest[..content_len].to_string();
+
+          i = tag_end + 1 + content.chars().count() + tag.chars().count();
+          tokens.push(Token::Str(content));
+        }
+        _ => {
+          tokens.push(Token::Punct(c));
+          i += 1;
+        }
+      }
+    } else if c.is_ascii_digit() {
+      let start = i;
+
+      while chars.get(i).is_some_and(|c| c.is_ascii_alphanumeric() || *c == '.') {
+        i += 1;
+      }
+
+      tokens.push(Token::Number(chars[start..i].iter().collect()));
+    } else if c.is_alphabetic() || c == '_' {
+      let start = i;
+
+      while chars
+        .get(i)
+        .is_some_and(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+      {
+        i += 1;
+      }
+
+      tokens.push(Token::Word(chars[start..i].iter().collect()));
+    } else {
+      tokens.push(Token::Punct(c));
+      i += 1;
+    }
+  }
+
+  Ok(tokens)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+  Str(String),
+  Int(i128),
+  Null,
+  // Any expression other than a literal
+  Other(String),
+}
+
+impl std::fmt::Display for Value {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Str(value) => write!(f, "'{value}'"),
+      Self::Int(value) => write!(f, "{value}"),
+      Self::Null => write!(f, "NULL"),
+      Self::Other(expr) => write!(f, "{expr}"),
+    }
+  }
+}
+
+#[derive(Default)]
+struct Table {
+  name: String,
+  columns: Vec<String>,
+  rows: Vec<Vec<(String, Value)>>,
+  // A statement that changes the rows in a way that cannot be reconstructed
+  unsupported: Option<String>,
+}
+
+// The state of the database after applying the migrations
+#[derive(Default)]
+struct Schema {
+  types: Vec<(String, Vec<String>)>,
+  tables: Vec<Table>,
+}
+
+fn find<'a, T>(items: &'a mut [T], name: &str, get_name: impl Fn(&T) -> &str) -> Option<&'a mut T> {
+  items
+    .iter_mut()
+    .find(|item| get_name(item).eq_ignore_ascii_case(name))
+}
+
+impl Schema {
+  fn custom_type(&mut self, name: &str) -> Option<&mut Vec<String>> {
+    find(&mut self.types, name, |(type_name, _)| type_name).map(|(_, values)| values)
+  }
+
+  fn table(&mut self, name: &str) -> Option<&mut Table> {
+    find(&mut self.tables, name, |table| &table.name)
+  }
+}
+
+// A cursor over the tokens of a single statement
+struct Statement<'a> {
+  tokens: &'a [Token],
+  pos: usize,
+}
+
+impl<'a> Statement<'a> {
+  fn peek(&self) -> Option<&'a Token> {
+    self.tokens.get(self.pos)
+  }
+
+  fn next(&mut self) -> Option<&'a Token> {
+    let token = self.tokens.get(self.pos);
+    self.pos += 1;
+    token
+  }
+
+  fn eat_keyword(&mut self, keyword: &str) -> bool {
+    let found = self.peek().is_some_and(|token| token.is_keyword(keyword));
+
+    if found {
+      self.pos += 1;
+    }
+
+    found
+  }
+
+  fn eat_keywords(&mut self, keywords: &[&str]) -> bool {
+    let found = keywords
+      .iter()
+      .enumerate()
+      .all(|(i, keyword)| self.tokens.get(self.pos + i).is_some_and(|token| token.is_keyword(keyword)));
+
+    if found {
+      self.pos += keywords.len();
+    }
+
+    found
+  }
+
+  fn eat_punct(&mut self, punct: char) -> bool {
+    let found = self.peek() == Some(&Token::Punct(punct));
+
+    if found {
+      self.pos += 1;
+    }
+
+    found
+  }
+
+  // Reads a possibly qualified name, and returns its last segment
+  fn name(&mut self) -> Result<String, String> {
+    let mut name = self
+      .next()
+      .and_then(Token::identifier)
+      .ok_or("Expected a name")?;
+
+    while self.eat_punct('.') {
+      name = self
+        .next()
+        .and_then(Token::identifier)
+        .ok_or("Expected a name")?;
+    }
+
+    Ok(name.to_string())
+  }
+
+  fn string(&mut self) -> Result<String, String> {
+    match self.next() {
+      Some(Token::Str(value)) => Ok(value.clone()),
+      _ => Err("Expected a string literal".to_string()),
+    }
+  }
+
+  // Splits the content of the parenthesized list at the cursor into its comma-separated elements
+  fn list(&mut self) -> Result<Vec<&'a [Token]>, String> {
+    if !self.eat_punct('(') {
+      return Err("Expected `(`".to_string());
+    }
+
+    let mut elements: Vec<&'a [Token]> = Vec::new();
+    let mut depth = 0;
+    let mut start = self.pos;
+
+    loop {
+      match self.next().ok_or("Unclosed `(`")? {
+        Token::Punct('(') => depth += 1,
+        Token::Punct(')') if depth == 0 => {
+          if self.pos - 1 > start {
+            elements.push(&self.tokens[start..self.pos - 1]);
+          }
+
+          return Ok(elements);
+        }
+        Token::Punct(')') => depth -= 1,
+        Token::Punct(',') if depth == 0 => {
+          elements.push(&self.tokens[start..self.pos - 1]);
+          start = self.pos;
+        }
+        _ => {}
+      };
+    }
+  }
+}
+
+fn parse_value(tokens: &[Token]) -> Value {
+  match tokens {
+    [Token::Str(value)] => Value::Str(value.clone()),
+    // Casts such as `'value'::text`
+    [Token::Str(value), Token::Punct(':'), Token::Punct(':'), ..] => Value::Str(value.clone()),
+    [Token::Number(number)] => number
+      .parse()
+      .map_or_else(|_| Value::Other(number.clone()), Value::Int),
+    [Token::Punct('-'), Token::Number(number)] => number
+      .parse::<i128>()
+      .map_or_else(|_| Value::Other(format!("-{number}")), |value| Value::Int(-value)),
+    [token] if token.is_keyword("null") => Value::Null,
+    _ => Value::Other(
+      tokens
+        .iter()
+        .map(|token| match token {
+          Token::Word(word) => word.clone(),
+          Token::Quoted(name) => format!("\"{name}\""),
+          Token::Str(value) => format!("'{value}'"),
+          Token::Number(number) => number.clone(),
+          Token::Punct(punct) => punct.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" "),
+    ),
+  }
+}
+
+fn apply_statement(schema: &mut Schema, tokens: &[Token]) -> Result<(), String> {
+  let mut stmt = Statement { tokens, pos: 0 };
+
+  if stmt.eat_keywords(&["create", "type"]) {
+    let name = stmt.name()?;
+
+    if !stmt.eat_keywords(&["as", "enum"]) {
+      return Ok(());
+    }
+
+    let values = stmt
+      .list()?
+      .into_iter()
+      .map(|element| match element {
+        [Token::Str(value)] => Ok(value.clone()),
+        _ => Err(format!("Invalid value in the definition of the type `{name}`")),
+      })
+      .collect::<Result<Vec<String>, String>>()?;
+
+    schema.types.retain(|(type_name, _)| !type_name.eq_ignore_ascii_case(&name));
+    schema.types.push((name, values));
+  } else if stmt.eat_keywords(&["alter", "type"]) {
+    let name = stmt.name()?;
+
+    let Some(values) = schema.custom_type(&name) else {
+      return Ok(());
+    };
+
+    if stmt.eat_keywords(&["add", "value"]) {
+      let if_not_exists = stmt.eat_keywords(&["if", "not", "exists"]);
+      let value = stmt.string()?;
+
+      if values.contains(&value) {
+        return if if_not_exists {
+          Ok(())
+        } else {
+          Err(format!("The value '{value}' already exists in the type `{name}`"))
+        };
+      }
+
+      let position = if stmt.eat_keyword("before") || stmt.eat_keyword("after") {
+        let is_after = stmt.tokens[stmt.pos - 1].is_keyword("after");
+        let neighbor = stmt.string()?;
+
+        let index = values
+          .iter()
+          .position(|value| *value == neighbor)
+          .ok_or_else(|| format!("The value '{neighbor}' does not exist in the type `{name}`"))?;
+
+        if is_after { index + 1 } else { index }
+      } else {
+        values.len()
+      };
+
+      values.insert(position, value);
+    } else if stmt.eat_keywords(&["rename", "value"]) {
+      let old_value = stmt.string()?;
+
+      if !stmt.eat_keyword("to") {
+        return Err("Expected `TO`".to_string());
+      }
+
+      let new_value = stmt.string()?;
+
+      let value = values
+        .iter_mut()
+        .find(|value| **value == old_value)
+        .ok_or_else(|| format!("The value '{old_value}' does not exist in the type `{name}`"))?;
+
+      *value = new_value;
+    } else if stmt.eat_keywords(&["rename", "to"]) {
+      let new_name = stmt.name()?;
+
+      if let Some((type_name, _)) = find(&mut schema.types, &name, |(type_name, _)| type_name) {
+        *type_name = new_name;
+      }
+    }
+  } else if stmt.eat_keywords(&["drop", "type"]) {
+    stmt.eat_keywords(&["if", "exists"]);
+
+    loop {
+      let name = stmt.name()?;
+
+      schema.types.retain(|(type_name, _)| !type_name.eq_ignore_ascii_case(&name));
+
+      if !stmt.eat_punct(',') {
+        break;
+      }
+    }
+  } else if stmt.eat_keyword("create") {
+    while ["temp", "temporary", "unlogged"]
+      .iter()
+      .any(|keyword| stmt.eat_keyword(keyword))
+    {}
+
+    if !stmt.eat_keyword("table") {
+      return Ok(());
+    }
+
+    let if_not_exists = stmt.eat_keywords(&["if", "not", "exists"]);
+    let name = stmt.name()?;
+
+    if stmt.peek() != Some(&Token::Punct('(')) || (if_not_exists && schema.table(&name).is_some()) {
+      return Ok(());
+    }
+
+    const CONSTRAINTS: [&str; 7] = [
+      "constraint", "primary", "foreign", "unique", "check", "exclude", "key",
+    ];
+
+    let columns: Vec<String> = stmt
+      .list()?
+      .into_iter()
+      .filter_map(|element| element.first())
+      .filter(|token| !CONSTRAINTS.iter().any(|keyword| token.is_keyword(keyword)))
+      .filter_map(|token| token.identifier().map(str::to_string))
+      .collect();
+
+    schema.tables.retain(|table| !table.name.eq_ignore_ascii_case(&name));
+    schema.tables.push(Table {
+      name,
+      columns,
+      ..Default::default()
+    });
+  } else if stmt.eat_keywords(&["drop", "table"]) {
+    stmt.eat_keywords(&["if", "exists"]);
+
+    loop {
+      let name = stmt.name()?;
+
+      schema.tables.retain(|table| !table.name.eq_ignore_ascii_case(&name));
+
+      if !stmt.eat_punct(',') {
+        break;
+      }
+    }
+  } else if stmt.eat_keywords(&["alter", "table"]) {
+    stmt.eat_keywords(&["if", "exists"]);
+    stmt.eat_keyword("only");
+
+    let name = stmt.name()?;
+
+    let Some(table) = schema.table(&name) else {
+      return Ok(());
+    };
+
+    if stmt.eat_keywords(&["rename", "to"]) {
+      table.name = stmt.name()?;
+    } else if stmt.eat_keyword("add") {
+      stmt.eat_keyword("column");
+      stmt.eat_keywords(&["if", "not", "exists"]);
+
+      if let Some(column) = stmt.next().and_then(Token::identifier)
+        && !table.columns.iter().any(|existing| existing.eq_ignore_ascii_case(column))
+      {
+        table.columns.push(column.to_string());
+      }
+    } else if stmt.eat_keywords(&["rename", "column"]) || stmt.eat_keyword("rename") {
+      let old_name = stmt.name()?;
+
+      if !stmt.eat_keyword("to") {
+        return Err("Expected `TO`".to_string());
+      }
+
+      let new_name = stmt.name()?;
+
+      for column in &mut table.columns {
+        if column.eq_ignore_ascii_case(&old_name) {
+          column.clone_from(&new_name);
+        }
+      }
+
+      for row in &mut table.rows {
+        for (column, _) in row {
+          if column.eq_ignore_ascii_case(&old_name) {
+            column.clone_from(&new_name);
+          }
+        }
+      }
+    }
+  } else if stmt.eat_keywords(&["insert", "into"]) || stmt.eat_keywords(&["insert", "or"]) {
+    // `INSERT OR IGNORE INTO` and similar sqlite variants
+    if tokens[1].is_keyword("or") {
+      stmt.next();
+
+      if !stmt.eat_keyword("into") {
+        return Err("Expected `INTO`".to_string());
+      }
+    }
+
+    let name = stmt.name()?;
+
+    let Some(table) = schema.table(&name) else {
+      return Ok(());
+    };
+
+    let columns: Vec<String> = if stmt.peek() == Some(&Token::Punct('(')) {
+      stmt
+        .list()?
+        .into_iter()
+        .map(|element| match element {
+          [column] => column
+            .identifier()
+            .map(str::to_string)
+            .ok_or_else(|| format!("Invalid column list in the insert into `{name}`")),
+          _ => Err(format!("Invalid column list in the insert into `{name}`")),
+        })
+        .collect::<Result<_, _>>()?
+    } else {
+      table.columns.clone()
+    };
+
+    if !stmt.eat_keyword("values") {
+      table.unsupported = Some(format!("an insert into `{name}` that does not use `VALUES`"));
+      return Ok(());
+    }
+
+    loop {
+      let values = stmt.list()?;
+
+      if values.len() != columns.len() {
+        return Err(format!(
+          "An insert into `{name}` has {} values, but {} columns",
+          values.len(),
+          columns.len()
+        ));
+      }
+
+      table.rows.push(
+        columns
+          .iter()
+          .cloned()
+          .zip(values.into_iter().map(parse_value))
+          .collect(),
+      );
+
+      if !stmt.eat_punct(',') {
+        break;
+      }
+    }
+  } else if stmt.eat_keyword("truncate") {
+    stmt.eat_keyword("table");
+
+    if let Some(table) = schema.table(&stmt.name()?) {
+      table.rows.clear();
+    }
+  } else if stmt.eat_keywords(&["delete", "from"]) || stmt.eat_keyword("update") {
+    let is_delete = tokens[0].is_keyword("delete");
+    let name = stmt.name()?;
+
+    if let Some(table) = schema.table(&name) {
+      if is_delete && stmt.peek().is_none() {
+        table.rows.clear();
+      } else {
+        let kind = if is_delete { "a delete from" } else { "an update of" };
+
+        table.unsupported = Some(format!("{kind} `{name}`"));
+      }
+    }
+  }
+
+  Ok(())
+}
+
+// Applies all the statements of a migration file
+fn apply_sql(schema: &mut Schema, sql: &str) -> Result<(), String> {
+  let tokens = tokenize(sql)?;
+
+  for statement in tokens.split(|token| *token == Token::Punct(';')) {
+    if !statement.is_empty() {
+      apply_statement(schema, statement)?;
+    }
+  }
+
+  Ok(())
+}
+
+// Maps the aliases of the variants to their canonical names
+fn canonical_name<'a>(aliases: &HashMap<&'a str, &'a str>, name: &'a str) -> &'a str {
+  aliases.get(name).copied().unwrap_or(name)
+}
+
+// The `up.sql` files of the migrations, in the order in which they are applied
+fn migration_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+  let entries = fs::read_dir(dir)
+    .map_err(|e| format!("Failed to read the migrations directory `{}`: {e}", dir.display()))?;
+
+  let mut files: Vec<PathBuf> = entries
+    .filter_map(|entry| entry.ok())
+    .map(|entry| entry.path().join("up.sql"))
+    .filter(|path| path.is_file())
+    .collect();
+
+  files.sort();
+
+  if files.is_empty() {
+    return Err(format!("No `up.sql` files were found in `{}`", dir.display()));
+  }
+
+  Ok(files)
+}
+
+/// The database source of the enum, along with the values that are needed to check it.
+pub enum MigrationsSource<'a> {
+  CustomType {
+    name: &'a str,
+    check_order: bool,
+  },
+  Table {
+    table_name: &'a str,
+    column_name: &'a str,
+    key_column: Option<&'a KeyColumn<'a>>,
+  },
+}
+
+impl<'a> MigrationsSource<'a> {
+  pub fn new(
+    db_type: Option<&'a NameTypes>,
+    check_order: bool,
+    table_name: &'a str,
+    column_name: &'a str,
+    key_column: Option<&'a KeyColumn<'a>>,
+  ) -> Result<Self, &'static str> {
+    match db_type {
+      Some(NameTypes::Custom { name }) => Ok(Self::CustomType { name, check_order }),
+      Some(NameTypes::MysqlEnum) => Err("`migrations` cannot be used with mysql enum columns"),
+      _ => Ok(Self::Table {
+        table_name,
+        column_name,
+        key_column,
+      }),
+    }
+  }
+}
+
+// Paths that start with `./` or `../` are relative to the file that contains the enum, like with `include_str!`, and every other path is relative to the root of the crate
+fn migrations_dir(attr_ident: &Ident, migrations_path: &str) -> Result<PathBuf, Error> {
+  if migrations_path.starts_with("./") || migrations_path.starts_with("../") {
+    let source_file = attr_ident.span().unwrap().local_file().ok_or_else(|| {
+      spanned_error!(
+        attr_ident,
+        "Failed to find the file that contains the enum, which is needed to resolve the path of the migrations"
+      )
+    })?;
+
+    // The path of the file is relative to the working directory of the compiler, but the paths in `include_bytes!` are relative to the file
+    std::path::absolute(source_file.parent().unwrap_or(Path::new("")).join(migrations_path))
+      .map_err(|e| spanned_error!(attr_ident, format!("Failed to resolve the path `{migrations_path}`: {e}")))
+  } else {
+    // A relative path would be resolved from the file in `include_bytes!`
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").map_err(|_| {
+      spanned_error!(
+        attr_ident,
+        "`CARGO_MANIFEST_DIR` is not set, so the path of the migrations cannot be resolved from the root of the crate. Use a path that starts with `./` or `../` to resolve it from the current file instead"
+      )
+    })?;
+
+    Ok(Path::new(&manifest_dir).join(migrations_path))
+  }
+}
+
+/// Checks the variants against the values created by the migrations in the given directory, and returns the tokens that make the compiler track the migration files.
+pub fn check_migrations(
+  enum_name: &Ident,
+  attr_ident: &Ident,
+  migrations_path: &str,
+  source: &MigrationsSource,
+  variants_data: &[VariantData],
+  fallback: Option<&Ident>,
+) -> Result<TokenStream2, Error> {
+  let dir = migrations_dir(attr_ident, migrations_path)?;
+
+  let files = migration_files(&dir).map_err(|e| spanned_error!(attr_ident, e))?;
+
+  let mut schema = Schema::default();
+
+  for file in &files {
+    let sql = fs::read_to_string(file)
+      .map_err(|e| spanned_error!(attr_ident, format!("Failed to read `{}`: {e}", file.display())))?;
+
+    let file_error = |e: String| {
+      spanned_error!(
+        attr_ident,
+        format!("Failed to parse `{}`: {e}", file.display())
+      )
+    };
+
+    apply_sql(&mut schema, &sql).map_err(file_error)?;
+  }
+
+  let mut errors: Vec<Error> = Vec::new();
+
+  let enum_name_str = enum_name.to_string();
+
+  let aliases: HashMap<&str, &str> = variants_data
+    .iter()
+    .flat_map(|data| {
+      data
+        .aliases
+        .iter()
+        .map(|alias| (alias.as_str(), data.db_name.as_str()))
+    })
+    .collect();
+
+  let (source_description, db_names) = match source {
+    MigrationsSource::CustomType { name, .. } => {
+      let values = schema.custom_type(name).ok_or_else(|| {
+        spanned_error!(
+          attr_ident,
+          format!("The type `{name}` is not created by the migrations at `{migrations_path}`")
+        )
+      })?;
+
+      (format!("the type `{name}`"), values.clone())
+    }
+    MigrationsSource::Table {
+      table_name,
+      column_name,
+      key_column,
+    } => {
+      let table = schema.table(table_name).ok_or_else(|| {
+        spanned_error!(
+          attr_ident,
+          format!("The table `{table_name}` is not created by the migrations at `{migrations_path}`")
+        )
+      })?;
+
+      if let Some(statement) = &table.unsupported {
+        return Err(spanned_error!(
+          attr_ident,
+          format!("Cannot reconstruct the rows of `{table_name}`, because the migrations contain {statement}")
+        ));
+      }
+
+      let get_value = |row: &[(String, Value)], column: &str| -> Option<Value> {
+        row
+          .iter()
+          .find(|(name, _)| name.eq_ignore_ascii_case(column))
+          .map(|(_, value)| value.clone())
+      };
+
+      let mut db_names: Vec<String> = Vec::new();
+      let mut next_id: i128 = 1;
+
+      for row in &table.rows {
+        let name = match get_value(row, column_name) {
+          Some(Value::Str(name)) => name,
+          Some(value) => {
+            return Err(spanned_error!(
+              attr_ident,
+              format!("Expected a string literal for `{table_name}.{column_name}` in the migrations, found {value}")
+            ));
+          }
+          None => {
+            return Err(spanned_error!(
+              attr_ident,
+              format!("An insert into `{table_name}` in the migrations has no value for `{column_name}`")
+            ));
+          }
+        };
+
+        let variant = variants_data
+          .iter()
+          .find(|data| data.db_name == canonical_name(&aliases, &name));
+
+        match key_column {
+          Some(KeyColumn::Id(_)) => {
+            // Ids that are not set explicitly are generated, as with an autoincrement or serial column
+            let id = match get_value(row, "id") {
+              Some(Value::Int(id)) => id,
+              None | Some(Value::Null) => next_id,
+              Some(value) => {
+                return Err(spanned_error!(
+                  attr_ident,
+                  format!("Expected an integer literal for `{table_name}.id` in the migrations, found {value}")
+                ));
+              }
+            };
+
+            next_id = next_id.max(id + 1);
+
+            if let Some(variant) = variant && variant.id != id {
+              errors.push(spanned_error!(
+                &variant.ident,
+                format!(
+                  "The variant `{}` has the id {}, but the migrations at `{migrations_path}` insert '{name}' with the id {id}",
+                  variant.ident, variant.id
+                )
+              ));
+            }
+          }
+          Some(KeyColumn::Code(code_column)) => {
+            let code = match get_value(row, code_column) {
+              Some(Value::Str(code)) => code,
+              value => {
+                return Err(spanned_error!(
+                  attr_ident,
+                  format!(
+                    "Expected a string literal for `{table_name}.{code_column}` in the migrations, found {}",
+                    value.map_or_else(|| "no value".to_string(), |value| value.to_string())
+                  )
+                ));
+              }
+            };
+
+            if let Some(variant) = variant && variant.code.as_ref() != Some(&code) {
+              errors.push(spanned_error!(
+                &variant.ident,
+                format!(
+                  "The variant `{}` has the code {:?}, but the migrations at `{migrations_path}` insert '{name}' with the code {code:?}",
+                  variant.ident,
+                  variant.code.as_deref().unwrap_or_default()
+                )
+              ));
+            }
+          }
+          None => {}
+        };
+
+        db_names.push(name);
+      }
+
+      (format!("the table `{table_name}`"), db_names)
+    }
+  };
+
+  let found_names: HashSet<&str> = db_names.iter().map(|name| canonical_name(&aliases, name)).collect();
+
+  for variant in variants_data {
+    if !found_names.contains(variant.db_name.as_str()) {
+      errors.push(spanned_error!(
+        &variant.ident,
+        format!(
+          "The variant `{}` ('{}') is missing from {source_description} in the migrations at `{migrations_path}`",
+          variant.ident, variant.db_name
+        )
+      ));
+    }
+  }
+
+  let mut missing_variants: Vec<&String> = db_names
+    .iter()
+    .filter(|name| {
+      let name = canonical_name(&aliases, name);
+
+      !variants_data.iter().any(|data| data.db_name == name)
+    })
+    .collect();
+
+  missing_variants.sort();
+
+  // With a fallback variant, the values that only exist in the database can still be read
+  if !missing_variants.is_empty() && fallback.is_none() {
+    errors.push(spanned_error!(
+      enum_name,
+      format!(
+        "The values {missing_variants:?} from {source_description} in the migrations at `{migrations_path}` are not mapped by the rust enum `{enum_name_str}`"
+      )
+    ));
+  }
+
+  if let MigrationsSource::CustomType {
+    check_order: true, ..
+  } = source
+  {
+    let mut seen_names: HashSet<&str> = HashSet::new();
+
+    let found: Vec<&str> = db_names
+      .iter()
+      .map(|name| canonical_name(&aliases, name))
+      .filter(|name| variants_data.iter().any(|data| data.db_name == *name) && seen_names.insert(name))
+      .collect();
+
+    let expected: Vec<&str> = variants_data
+      .iter()
+      .map(|data| data.db_name.as_str())
+      .filter(|name| found.contains(name))
+      .collect();
+
+    if expected != found {
+      errors.push(spanned_error!(
+        enum_name,
+        format!(
+          "The order of the variants of `{enum_name_str}` does not match {source_description} in the migrations at `{migrations_path}`.\n Expected: {expected:?}\n Found: {found:?}"
+        )
+      ));
+    }
+  }
+
+  let mut errors = errors.into_iter();
+
+  if let Some(mut error) = errors.next() {
+    for other in errors {
+      error.combine(other);
+    }
+
+    return Err(error);
+  }
+
+  let file_paths = files.iter().map(|file| file.to_string_lossy().to_string());
+
+  Ok(quote! {
+    // Makes the compiler rebuild the enum when the existing migrations change. New migrations are not tracked, which requires a `rerun-if-changed` in the build script of the crate
+    #(const _: &[u8] = include_bytes!(#file_paths);)*
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn apply(sql: &str) -> Result<Schema, String> {
+    let mut schema = Schema::default();
+
+    apply_sql(&mut schema, sql)?;
+
+    Ok(schema)
+  }
+
+  fn type_values(schema: &mut Schema, name: &str) -> Vec<String> {
+    schema.custom_type(name).unwrap().clone()
+  }
+
+  fn names(table: &mut Table) -> Vec<Value> {
+    table
+      .rows
+      .iter()
+      .map(|row| row.iter().find(|(column, _)| column == "name").unwrap().1.clone())
+      .collect()
+  }
+
+  #[test]
+  fn quoting() {
+    let tokens = tokenize(r#"'it''s' "quoted ""name""" `backticks` $$dollar ; quoted$$ $tag$x$tag$"#).unwrap();
+
+    assert_eq!(
+      tokens,
+      [
+        Token::Str("it's".to_string()),
+        Token::Quoted("quoted \"name\"".to_string()),
+        Token::Quoted("backticks".to_string()),
+        Token::Str("dollar ; quoted".to_string()),
+        Token::Str("x".to_string()),
+      ]
+    );
+
+    assert!(tokenize("'unterminated").is_err());
+    assert!(tokenize("$tag$unterminated").is_err());
+
+    let mut schema = apply(r#"CREATE TYPE "Element" AS ENUM ('fire', 'it''s; water');"#).unwrap();
+
+    assert_eq!(type_values(&mut schema, "element"), ["fire", "it's; water"]);
+  }
+
+  #[test]
+  fn comments() {
+    let mut schema = apply(
+      "
+      -- CREATE TYPE commented AS ENUM ('a');
+      CREATE TYPE element AS ENUM (
+        'fire', -- 'water',
+        /* 'grass', */ 'electric'
+      );
+      /* DROP TYPE element; */
+      ",
+    )
+    .unwrap();
+
+    assert!(schema.custom_type("commented").is_none());
+    assert_eq!(type_values(&mut schema, "element"), ["fire", "electric"]);
+  }
+
+  #[test]
+  fn alter_type_add_value() {
+    let mut schema = apply(
+      "
+      CREATE TYPE public.element AS ENUM ('fire', 'water');
+      ALTER TYPE element ADD VALUE 'grass';
+      ALTER TYPE element ADD VALUE 'electric' BEFORE 'water';
+      ALTER TYPE element ADD VALUE 'ice' AFTER 'fire';
+      ALTER TYPE element ADD VALUE IF NOT EXISTS 'fire';
+      ALTER TYPE element RENAME VALUE 'ice' TO 'frost';
+      ",
+    )
+    .unwrap();
+
+    assert_eq!(
+      type_values(&mut schema, "element"),
+      ["fire", "frost", "electric", "water", "grass"]
+    );
+
+    assert_eq!(
+      apply("CREATE TYPE element AS ENUM ('fire'); ALTER TYPE element ADD VALUE 'fire';").err(),
+      Some("The value 'fire' already exists in the type `element`".to_string())
+    );
+
+    assert_eq!(
+      apply("CREATE TYPE element AS ENUM ('fire'); ALTER TYPE element ADD VALUE 'water' AFTER 'grass';").err(),
+      Some("The value 'grass' does not exist in the type `element`".to_string())
+    );
+  }
+
+  #[test]
+  fn drop() {
+    let mut schema = apply(
+      "
+      CREATE TYPE element AS ENUM ('fire');
+      CREATE TYPE weather AS ENUM ('rain');
+      CREATE TABLE rarities (id SERIAL PRIMARY KEY, name TEXT NOT NULL);
+      CREATE TABLE regions (code TEXT PRIMARY KEY, name TEXT NOT NULL);
+      INSERT INTO rarities (name) VALUES ('common');
+      DROP TYPE IF EXISTS element, weather;
+      DROP TABLE rarities;
+      CREATE TABLE rarities (id SERIAL PRIMARY KEY, name TEXT NOT NULL);
+      INSERT INTO regions VALUES ('KA', 'kanto'), ('JO', 'johto');
+      TRUNCATE TABLE regions;
+      INSERT INTO regions VALUES ('HO', 'hoenn');
+      ",
+    )
+    .unwrap();
+
+    assert!(schema.custom_type("element").is_none());
+    assert!(schema.custom_type("weather").is_none());
+    assert!(schema.table("rarities").unwrap().rows.is_empty());
+    assert_eq!(
+      names(schema.table("regions").unwrap()),
+      [Value::Str("hoenn".to_string())]
+    );
+  }
+
+  #[test]
+  fn inserts() {
+    let mut schema = apply(
+      "
+      CREATE TABLE IF NOT EXISTS rarities (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        CONSTRAINT unique_name UNIQUE (name)
+      );
+      INSERT OR IGNORE INTO rarities (id, name) VALUES (1, 'common'), (-2, 'rare'::text), (3, NULL);
+      ",
+    )
+    .unwrap();
+
+    let table = schema.table("rarities").unwrap();
+
+    assert_eq!(table.columns, ["id", "name"]);
+    assert_eq!(
+      table.rows[1],
+      [
+        ("id".to_string(), Value::Int(-2)),
+        ("name".to_string(), Value::Str("rare".to_string()))
+      ]
+    );
+    assert_eq!(
+      names(table),
+      [Value::Str("common".to_string()), Value::Str("rare".to_string()), Value::Null]
+    );
+
+    assert_eq!(
+      apply("CREATE TABLE rarities (id INTEGER, name TEXT); INSERT INTO rarities (name) VALUES (1, 'common');").err(),
+      Some("An insert into `rarities` has 2 values, but 1 columns".to_string())
+    );
+  }
+
+  #[test]
+  fn unsupported_statements() {
+    let mut schema = apply(
+      "
+      CREATE TABLE rarities (id SERIAL PRIMARY KEY, name TEXT NOT NULL);
+      CREATE TABLE regions (code TEXT PRIMARY KEY, name TEXT NOT NULL);
+      CREATE TABLE moves (id SERIAL PRIMARY KEY, name TEXT NOT NULL);
+      CREATE TABLE statuses (id SERIAL PRIMARY KEY, name TEXT NOT NULL);
+      INSERT INTO statuses (name) VALUES ('burned');
+      DELETE FROM statuses;
+      UPDATE rarities SET name = 'rare' WHERE id = 1;
+      DELETE FROM regions WHERE code = 'KA';
+      INSERT INTO moves (name) SELECT name FROM rarities;
+      CREATE INDEX rarities_name ON rarities (name);
+      CREATE FUNCTION noop() RETURNS void AS $$ DELETE FROM rarities WHERE id = 1; $$ LANGUAGE sql;
+      ",
+    )
+    .unwrap();
+
+    assert_eq!(schema.table("statuses").unwrap().unsupported, None);
+    assert!(schema.table("statuses").unwrap().rows.is_empty());
+
+    assert_eq!(
+      schema.table("rarities").unwrap().unsupported.as_deref(),
+      Some("an update of `rarities`")
+    );
+    assert_eq!(
+      schema.table("regions").unwrap().unsupported.as_deref(),
+      Some("a delete from `regions`")
+    );
+    assert_eq!(
+      schema.table("moves").unwrap().unsupported.as_deref(),
+      Some("an insert into `moves` that does not use `VALUES`")
+    );
+  }
+}
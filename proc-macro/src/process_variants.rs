@@ -1,15 +1,32 @@
-use std::{cmp::Ordering, ops::Range};
+use std::{
+  cmp::Ordering,
+  collections::HashMap,
+  ops::{Range, RangeInclusive},
+};
 
 use convert_case::{Case, Casing};
-use syn::{punctuated::Punctuated, Error, Ident, LitInt, LitStr, Token, Variant};
+use proc_macro2::Span;
+use syn::{
+  parenthesized, punctuated::Punctuated, spanned::Spanned, Error, Expr, Ident, LitInt, LitStr,
+  Token, Variant,
+};
+
+use crate::{
+  attributes::{extract_int, ExtraColumn},
+  sql_generation::sql_literal,
+};
 
 pub struct VariantData {
   pub ident: Ident,
   pub db_name: String,
-  pub id: i32,
+  pub aliases: Vec<String>,
+  pub id: i128,
+  pub code: Option<String>,
+  /// The values of the extra columns, in the same order as the `extra_columns` attribute.
+  pub extra_values: Vec<Expr>,
 }
 
-fn is_skipped(id: i32, sorted_ranges: &[Range<i32>]) -> bool {
+fn is_skipped(id: i128, sorted_ranges: &[Range<i128>]) -> bool {
   let result = sorted_ranges.binary_search_by(|range| {
     if range.contains(&id) {
       Ordering::Equal
@@ -23,32 +40,111 @@ fn is_skipped(id: i32, sorted_ranges: &[Range<i32>]) -> bool {
   result.is_ok()
 }
 
+fn id_range(rust_type: &Ident) -> RangeInclusive<i128> {
+  if rust_type == "i8" {
+    i8::MIN.into()..=i8::MAX.into()
+  } else if rust_type == "i16" {
+    i16::MIN.into()..=i16::MAX.into()
+  } else if rust_type == "i32" {
+    i32::MIN.into()..=i32::MAX.into()
+  } else if rust_type == "i64" {
+    i64::MIN.into()..=i64::MAX.into()
+  } else if rust_type == "u8" {
+    0..=u8::MAX.into()
+  } else if rust_type == "u16" {
+    0..=u16::MAX.into()
+  } else if rust_type == "u32" {
+    0..=u32::MAX.into()
+  } else {
+    0..=u64::MAX.into()
+  }
+}
+
+fn push_error(errors: &mut Option<Error>, error: Error) {
+  if let Some(errors) = errors {
+    errors.combine(error);
+  } else {
+    *errors = Some(error);
+  }
+}
+
+// The ids of the variants after a discriminant would skip the ranges, and no longer match their discriminants
+fn check_discriminant_skip_ids(errors: &mut Option<Error>, expr: &Expr, skip_ranges: &[Range<i128>]) {
+  if !skip_ranges.is_empty() {
+    push_error(
+      errors,
+      spanned_error!(
+        expr,
+        "Explicit discriminants cannot be used together with `skip_ids`"
+      ),
+    );
+  }
+}
+
 pub fn process_variants(
   variants: &Punctuated<Variant, Token![,]>,
   case: Case,
-  skip_ranges: &[Range<i32>],
-) -> Result<Vec<VariantData>, Error> {
+  skip_ranges: &[Range<i128>],
+  id_rust_type: Option<&Ident>,
+  extra_columns: &[ExtraColumn],
+  with_codes: bool,
+) -> Result<(Vec<VariantData>, Option<Ident>), Error> {
   let mut variants_data: Vec<VariantData> = Vec::new();
+  let mut fallback: Option<Ident> = None;
+  let mut errors: Option<Error> = None;
+
+  let mut used_names: HashMap<String, Ident> = HashMap::new();
+  let mut used_ids: HashMap<i128, Ident> = HashMap::new();
+  let mut used_codes: HashMap<String, Ident> = HashMap::new();
 
   let mut current_id = 1;
+  // Whether the ids follow the same numbering as rust, which is the case after the first explicit discriminant
+  let mut follows_discriminant = false;
 
   for variant in variants {
     let ident = variant.ident.clone();
-    let mut db_name: Option<String> = None;
-    let mut id: Option<i32> = None;
+    let mut db_name: Option<LitStr> = None;
+    let mut id: Option<LitInt> = None;
+    let mut code: Option<LitStr> = None;
+    let mut aliases: Vec<LitStr> = Vec::new();
+    let mut extra_values: Vec<(Ident, Expr)> = Vec::new();
+    let mut is_fallback = false;
 
     for attr in &variant.attrs {
       if attr.meta.path().is_ident("db_mapping") {
         attr.parse_nested_meta(|meta| {
           if meta.path.is_ident("id") {
             let val = meta.value()?;
-            id = Some(val.parse::<LitInt>()?.base10_parse::<i32>()?);
+            let lit = val.parse::<LitInt>()?;
+            lit.base10_parse::<i128>()?;
+
+            id = Some(lit);
           } else if meta.path.is_ident("name") {
             let val = meta.value()?;
 
-            db_name = Some(val.parse::<LitStr>()?.value());
+            db_name = Some(val.parse::<LitStr>()?);
+          } else if meta.path.is_ident("code") {
+            let val = meta.value()?;
+
+            code = Some(val.parse::<LitStr>()?);
+          } else if meta.path.is_ident("aliases") {
+            let content;
+            parenthesized!(content in meta.input);
+
+            aliases.extend(Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?);
+          } else if meta.path.is_ident("extra") {
+            meta.parse_nested_meta(|column_meta| {
+              let column = column_meta.path.require_ident()?.clone();
+              let value = column_meta.value()?.parse::<Expr>()?;
+
+              extra_values.push((column, value));
+
+              Ok(())
+            })?;
+          } else if meta.path.is_ident("other") {
+            is_fallback = true;
           } else {
-            return Err(meta.error("Unknown attribute. Allowed attributes are: [ id, name ]"));
+            return Err(meta.error("Unknown attribute. Allowed attributes are: [ id, name, code, aliases, extra, other ]"));
           }
 
           Ok(())
@@ -56,7 +152,98 @@ pub fn process_variants(
       }
     }
 
-    let id = id.unwrap_or_else(|| {
+    if is_fallback {
+      if id.is_some()
+        || db_name.is_some()
+        || code.is_some()
+        || !aliases.is_empty()
+        || !extra_values.is_empty()
+      {
+        push_error(
+          &mut errors,
+          spanned_error!(
+            &ident,
+            "The fallback variant cannot have a `name`, `code`, `aliases`, `extra` values or an `id`"
+          ),
+        );
+      } else if let Some(other) = &fallback {
+        push_error(
+          &mut errors,
+          spanned_error!(
+            &ident,
+            format!("Only one variant can be marked as `other`, already used by `{other}`")
+          ),
+        );
+      } else if !variant.fields.is_empty() {
+        push_error(
+          &mut errors,
+          spanned_error!(&ident, "The fallback variant cannot have fields"),
+        );
+      }
+
+      // The fallback variant has no id, but rust still counts it when numbering the variants after it
+      if id_rust_type.is_some() && let Some((_, expr)) = &variant.discriminant {
+        current_id = extract_int(expr)? + 1;
+        follows_discriminant = true;
+
+        check_discriminant_skip_ids(&mut errors, expr, skip_ranges);
+      } else if follows_discriminant {
+        current_id += 1;
+      }
+
+      fallback = Some(ident);
+
+      continue;
+    }
+
+    let discriminant = if id_rust_type.is_some() && let Some((_, expr)) = &variant.discriminant {
+      let value = extract_int(expr)?;
+
+      check_discriminant_skip_ids(&mut errors, expr, skip_ranges);
+
+      // Following the same numbering as rust for the variants after this one
+      current_id = value + 1;
+      follows_discriminant = true;
+
+      Some((value, expr))
+    } else {
+      None
+    };
+
+    let explicit_id = match (&id, discriminant) {
+      (Some(lit), Some((discriminant, expr))) => {
+        let value = lit.base10_parse::<i128>()?;
+
+        if value != discriminant {
+          push_error(
+            &mut errors,
+            spanned_error!(
+              expr,
+              format!("The discriminant `{discriminant}` of `{ident}` conflicts with the id `{value}` set with `db_mapping`")
+            ),
+          );
+        }
+
+        Some((value, lit.span()))
+      }
+      (Some(lit), None) => Some((lit.base10_parse::<i128>()?, lit.span())),
+      (None, Some((discriminant, expr))) => Some((discriminant, expr.span())),
+      (None, None) => None,
+    };
+
+    let (id, id_span) = if let Some((explicit_id, span)) = explicit_id {
+      if id_rust_type.is_some() && is_skipped(explicit_id, skip_ranges) {
+        push_error(
+          &mut errors,
+          error!(
+            span,
+            format!("The id `{explicit_id}` is inside the ranges listed in `skip_ids`")
+          ),
+        );
+      }
+
+      (explicit_id, span)
+    } else {
       while is_skipped(current_id, skip_ranges) {
         current_id += 1;
       }
@@ -65,15 +252,188 @@ pub fn process_variants(
 
       current_id += 1;
 
-      found_id
-    });
+      (found_id, ident.span())
+    };
+
+    let (db_name, name_span) = if let Some(lit) = &db_name {
+      (lit.value(), lit.span())
+    } else {
+      (variant.ident.to_string().to_case(case), ident.span())
+    };
+
+    check_name(&mut errors, &mut used_names, &db_name, name_span, &ident);
+
+    for alias in &aliases {
+      check_name(
+        &mut errors,
+        &mut used_names,
+        &alias.value(),
+        alias.span(),
+        &ident,
+      );
+    }
+
+    if let Some(rust_type) = id_rust_type {
+      check_id(&mut errors, &mut used_ids, id, id_span, &ident, rust_type);
+    }
+
+    let code = if with_codes {
+      if let Some(lit) = &code {
+        let value = lit.value();
+
+        if let Some(other) = used_codes.get(&value) {
+          push_error(
+            &mut errors,
+            spanned_error!(
+              lit,
+              format!("Duplicate code `{value}`, already used by `{other}`")
+            ),
+          );
+        } else {
+          used_codes.insert(value.clone(), ident.clone());
+        }
+
+        Some(value)
+      } else {
+        push_error(
+          &mut errors,
+          spanned_error!(
+            &ident,
+            "Missing `code` for this variant, which is required by `code_mapping`"
+          ),
+        );
+
+        None
+      }
+    } else {
+      if let Some(lit) = &code {
+        push_error(
+          &mut errors,
+          spanned_error!(lit, "`code` can only be used with `code_mapping`"),
+        );
+      }
+
+      None
+    };
+
+    let extra_values = match sort_extra_values(extra_values, extra_columns, &ident) {
+      Ok(values) => values,
+      Err(e) => {
+        push_error(&mut errors, e);
+        Vec::new()
+      }
+    };
 
     variants_data.push(VariantData {
       ident,
-      db_name: db_name.unwrap_or_else(|| variant.ident.to_string().to_case(case)),
+      db_name,
+      aliases: aliases.iter().map(|alias| alias.value()).collect(),
       id,
+      code,
+      extra_values,
     });
   }
 
-  Ok(variants_data)
+  if let Some(errors) = errors {
+    Err(errors)
+  } else {
+    Ok((variants_data, fallback))
+  }
+}
+
+fn sort_extra_values(
+  values: Vec<(Ident, Expr)>,
+  extra_columns: &[ExtraColumn],
+  ident: &Ident,
+) -> Result<Vec<Expr>, Error> {
+  let mut sorted_values: Vec<Option<Expr>> = vec![None; extra_columns.len()];
+
+  for (column, value) in values {
+    if sql_literal(&value).is_none() {
+      return Err(spanned_error!(
+        &value,
+        "Expected a string, number or boolean literal"
+      ));
+    }
+
+    let Some(index) = extra_columns.iter().position(|c| c.name == column) else {
+      return Err(spanned_error!(
+        &column,
+        format!("Unknown extra column `{column}`. Extra columns must be listed in the `extra_columns` attribute")
+      ));
+    };
+
+    if sorted_values[index].is_some() {
+      return Err(spanned_error!(
+        &column,
+        format!("Duplicate value for the extra column `{column}`")
+      ));
+    }
+
+    sorted_values[index] = Some(value);
+  }
+
+  sorted_values
+    .into_iter()
+    .zip(extra_columns)
+    .map(|(value, column)| {
+      value.ok_or_else(|| {
+        spanned_error!(
+          ident,
+          format!("Missing value for the extra column `{}`", column.name)
+        )
+      })
+    })
+    .collect()
+}
+
+fn check_name(
+  errors: &mut Option<Error>,
+  used_names: &mut HashMap<String, Ident>,
+  name: &str,
+  span: Span,
+  ident: &Ident,
+) {
+  if let Some(other) = used_names.get(name) {
+    push_error(
+      errors,
+      error!(
+        span,
+        format!("Duplicate database name `{name}`, already used by `{other}`")
+      ),
+    );
+  } else {
+    used_names.insert(name.to_string(), ident.clone());
+  }
+}
+
+fn check_id(
+  errors: &mut Option<Error>,
+  used_ids: &mut HashMap<i128, Ident>,
+  id: i128,
+  span: Span,
+  ident: &Ident,
+  rust_type: &Ident,
+) {
+  if !id_range(rust_type).contains(&id) {
+    push_error(
+      errors,
+      error!(
+        span,
+        format!("The id `{id}` for `{ident}` does not fit in the `{rust_type}` type used by the id mapping")
+      ),
+    );
+  }
+
+  if let Some(other) = used_ids.get(&id) {
+    push_error(
+      errors,
+      error!(
+        span,
+        format!("Duplicate id `{id}`, already used by `{other}`")
+      ),
+    );
+  } else {
+    used_ids.insert(id, ident.clone());
+  }
 }
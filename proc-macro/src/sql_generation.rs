@@ -0,0 +1,352 @@
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{Expr, Ident, Lit, LitInt, UnOp};
+
+use crate::{attributes::ExtraColumn, KeyColumn, TokenStream2, VariantData};
+
+fn sql_string(value: &str) -> String {
+  format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Converts the value of an extra column to its SQL representation, if it's a supported literal.
+pub fn sql_literal(expr: &Expr) -> Option<String> {
+  match expr {
+    Expr::Lit(expr_lit) => match &expr_lit.lit {
+      Lit::Str(value) => Some(sql_string(&value.value())),
+      Lit::Int(value) => Some(value.base10_digits().to_string()),
+      Lit::Float(value) => Some(value.base10_digits().to_string()),
+      Lit::Bool(value) => Some(if value.value { "TRUE" } else { "FALSE" }.to_string()),
+      _ => None,
+    },
+    Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => {
+      sql_literal(&unary.expr).map(|value| format!("-{value}"))
+    }
+    _ => None,
+  }
+}
+
+fn insert_columns(
+  column_name: &str,
+  extra_columns: &[ExtraColumn],
+  key_column: Option<&KeyColumn>,
+) -> String {
+  let mut columns: Vec<String> = Vec::new();
+
+  match key_column {
+    Some(KeyColumn::Id(_)) => columns.push("id".to_string()),
+    Some(KeyColumn::Code(code_column)) => columns.push(code_column.to_string()),
+    None => {}
+  };
+
+  columns.push(column_name.to_string());
+  columns.extend(extra_columns.iter().map(|column| column.name.to_string()));
+
+  columns.join(", ")
+}
+
+fn insert_values(data: &VariantData, key_column: Option<&KeyColumn>) -> String {
+  let mut values: Vec<String> = Vec::new();
+
+  match key_column {
+    Some(KeyColumn::Id(_)) => values.push(data.id.to_string()),
+    Some(KeyColumn::Code(_)) => values.push(sql_string(data.code.as_deref().unwrap_or_default())),
+    None => {}
+  };
+
+  values.push(sql_string(&data.db_name));
+  values.extend(data.extra_values.iter().filter_map(sql_literal));
+
+  format!("({})", values.join(", "))
+}
+
+pub fn create_type_sql(
+  enum_name: &Ident,
+  db_enum_name: &str,
+  variants_data: &[VariantData],
+) -> TokenStream2 {
+  let values: Vec<String> = variants_data
+    .iter()
+    .map(|data| sql_string(&data.db_name))
+    .collect();
+
+  let sql = format!(
+    "CREATE TYPE {db_enum_name} AS ENUM ({});",
+    values.join(", ")
+  );
+
+  quote! {
+    impl #enum_name {
+      /// Returns the `CREATE TYPE` statement for the custom postgres type that corresponds to this enum.
+      pub fn create_type_sql() -> String {
+        #sql.to_string()
+      }
+    }
+  }
+}
+
+pub fn seed_sql(
+  enum_name: &Ident,
+  table_name: &str,
+  column_name: &str,
+  variants_data: &[VariantData],
+  extra_columns: &[ExtraColumn],
+  key_column: Option<&KeyColumn>,
+) -> TokenStream2 {
+  let rows: Vec<String> = variants_data
+    .iter()
+    .map(|data| insert_values(data, key_column))
+    .collect();
+
+  let columns = insert_columns(column_name, extra_columns, key_column);
+
+  let sql = format!(
+    "INSERT INTO {table_name} ({columns}) VALUES {};",
+    rows.join(", ")
+  );
+
+  quote! {
+    impl #enum_name {
+      /// Returns the `INSERT` statement that populates the lookup table for this enum with all of its variants.
+      pub fn seed_sql() -> String {
+        #sql.to_string()
+      }
+    }
+  }
+}
+
+// Updates the extra values of a row, only if any of them differs, so that the number of affected rows tells if anything changed.
+// `IS NOT TRUE` also updates the rows that have null values
+fn update_extra_values_sql(
+  data: &VariantData,
+  table_name: &str,
+  column_name: &str,
+  extra_columns: &[ExtraColumn],
+) -> Option<String> {
+  if extra_columns.is_empty() {
+    return None;
+  }
+
+  let assignments: Vec<String> = extra_columns
+    .iter()
+    .zip(data.extra_values.iter().filter_map(sql_literal))
+    .map(|(column, value)| format!("{} = {value}", column.name))
+    .collect();
+
+  Some(format!(
+    "UPDATE {table_name} SET {} WHERE {column_name} = {} AND ({}) IS NOT TRUE;",
+    assignments.join(", "),
+    sql_string(&data.db_name),
+    assignments.join(" AND ")
+  ))
+}
+
+pub fn sync_to_db(
+  enum_name: &Ident,
+  table_path: &TokenStream2,
+  table_name: &str,
+  column_name: &str,
+  key_column: Option<&KeyColumn>,
+  variants_data: &[VariantData],
+  extra_columns: &[ExtraColumn],
+) -> TokenStream2 {
+  let column_name_ident = format_ident!("{column_name}");
+  let insert_columns = insert_columns(column_name, extra_columns, key_column);
+
+  // The names come from the database, so they are passed as bound parameters
+  let delete_query = quote! {
+    if matches!(policy, diesel_enums::SyncPolicy::Full) {
+      for name in &db_names {
+        if !rust_variants.iter().any(|variant| variant.0 == name) {
+          diesel::delete(#table_path::table.filter(#table_path::#column_name_ident.eq(name.as_str())))
+            .execute(conn)?;
+
+          report.deleted.push(name.clone());
+        }
+      }
+    }
+  };
+
+  let delete_bound = quote! {
+    for<'name> diesel::dsl::delete<diesel::dsl::Filter<#table_path::table, diesel::dsl::Eq<#table_path::#column_name_ident, &'name str>>>:
+      diesel::query_dsl::methods::ExecuteDsl<C>,
+  };
+
+  let update_extra_values = quote! {
+    if matches!(policy, diesel_enums::SyncPolicy::Full)
+      && let Some(update_sql) = update_sql
+      && diesel::sql_query(update_sql).execute(conn)? > 0
+    {
+      report.updated_values.push(name.to_string());
+    }
+  };
+
+  let rust_variants = variants_data.iter().map(|data| {
+    let db_name = &data.db_name;
+    let insert_sql = format!(
+      "INSERT INTO {table_name} ({insert_columns}) VALUES {};",
+      insert_values(data, key_column)
+    );
+    let update_sql = match update_extra_values_sql(data, table_name, column_name, extra_columns) {
+      Some(sql) => quote! { Some(#sql) },
+      None => quote! { None },
+    };
+
+    let key = match key_column {
+      Some(KeyColumn::Id(rust_type)) => {
+        let id = LitInt::new(&format!("{}{}", data.id, rust_type), Span::call_site());
+
+        quote! { #id, }
+      }
+      Some(KeyColumn::Code(_)) => {
+        let code = data.code.as_deref().unwrap_or_default();
+
+        quote! { #code, }
+      }
+      None => TokenStream2::new(),
+    };
+
+    quote! { (#db_name, #key #insert_sql, #update_sql) }
+  });
+
+  let variants_count = variants_data.len();
+
+  let (signature, body) = if let Some(key_column) = key_column {
+    let (key_ident, key_type, key_literal_type, updated_key) = match key_column {
+      KeyColumn::Id(rust_type) => (
+        format_ident!("id"),
+        quote! { #rust_type },
+        quote! { #rust_type },
+        quote! {
+          report.updated_ids.push(diesel_enums::UpdatedId {
+            name: name.to_string(),
+            old_id: i128::from(*db_key),
+            new_id: i128::from(key),
+          });
+        },
+      ),
+      KeyColumn::Code(code_column) => (
+        format_ident!("{code_column}"),
+        quote! { String },
+        quote! { &'static str },
+        quote! {
+          report.updated_codes.push(diesel_enums::UpdatedCode {
+            name: name.to_string(),
+            old_code: db_key.clone(),
+            new_code: key.to_string(),
+          });
+        },
+      ),
+    };
+
+    (
+      quote! {
+        pub fn sync_to_db<C>(conn: &mut C, policy: diesel_enums::SyncPolicy) -> diesel::QueryResult<diesel_enums::SyncReport>
+        where
+          C: diesel::Connection,
+          diesel::query_builder::SqlQuery: diesel::query_dsl::methods::ExecuteDsl<C>,
+          #delete_bound
+          for<'query> diesel::dsl::Select<#table_path::table, (#table_path::#key_ident, #table_path::#column_name_ident)>:
+            diesel::query_dsl::LoadQuery<'query, C, (#key_type, String)>,
+      },
+      quote! {
+        let rust_variants: [(&str, #key_literal_type, &str, Option<&str>); #variants_count] = [ #(#rust_variants),* ];
+
+        conn.transaction(|conn| {
+          let db_variants: Vec<(#key_type, String)> = #table_path::table
+            .select((#table_path::#key_ident, #table_path::#column_name_ident))
+            .load(conn)?;
+
+          let db_names: Vec<String> = db_variants.iter().map(|(_, name)| name.clone()).collect();
+
+          let mut report = diesel_enums::SyncReport::default();
+
+          #delete_query
+
+          // The rows are only inserted after all the deletions, so that they do not conflict with the keys of the rows that are being replaced
+          let mut inserts: Vec<&str> = Vec::new();
+
+          for (name, key, insert_sql, update_sql) in rust_variants {
+            match db_variants.iter().find(|(_, db_name)| db_name == name) {
+              // The rows with a mismatched key are inserted again, since updating the keys one by one could conflict when they are swapped between rows
+              Some((db_key, _)) if *db_key != key => {
+                if matches!(policy, diesel_enums::SyncPolicy::Full) {
+                  diesel::delete(#table_path::table.filter(#table_path::#column_name_ident.eq(name)))
+                    .execute(conn)?;
+
+                  inserts.push(insert_sql);
+
+                  #updated_key
+                }
+              }
+              Some(_) => {
+                #update_extra_values
+              }
+              None => {
+                inserts.push(insert_sql);
+
+                report.inserted.push(name.to_string());
+              }
+            };
+          }
+
+          for insert_sql in inserts {
+            diesel::sql_query(insert_sql).execute(conn)?;
+          }
+
+          Ok(report)
+        })
+      },
+    )
+  } else {
+    (
+      quote! {
+        pub fn sync_to_db<C>(conn: &mut C, policy: diesel_enums::SyncPolicy) -> diesel::QueryResult<diesel_enums::SyncReport>
+        where
+          C: diesel::Connection,
+          diesel::query_builder::SqlQuery: diesel::query_dsl::methods::ExecuteDsl<C>,
+          #delete_bound
+          for<'query> diesel::dsl::Select<#table_path::table, #table_path::#column_name_ident>:
+            diesel::query_dsl::LoadQuery<'query, C, String>,
+      },
+      quote! {
+        let rust_variants: [(&str, &str, Option<&str>); #variants_count] = [ #(#rust_variants),* ];
+
+        conn.transaction(|conn| {
+          let db_names: Vec<String> = #table_path::table
+            .select(#table_path::#column_name_ident)
+            .load(conn)?;
+
+          let mut report = diesel_enums::SyncReport::default();
+
+          #delete_query
+
+          for (name, insert_sql, update_sql) in rust_variants {
+            if db_names.iter().any(|db_name| db_name == name) {
+              #update_extra_values
+            } else {
+              diesel::sql_query(insert_sql).execute(conn)?;
+
+              report.inserted.push(name.to_string());
+            }
+          }
+
+          Ok(report)
+        })
+      },
+    )
+  };
+
+  quote! {
+    impl #enum_name {
+      /// Synchronizes the lookup table for this enum with its variants, inserting the rows that are missing from it.
+      ///
+      /// With [`SyncPolicy::Full`](diesel_enums::SyncPolicy::Full), it also deletes the rows that do not correspond to any variant, replaces the rows whose id or code does not match and updates the values of the extra columns. All the changes are applied in a single transaction.
+      #signature
+      {
+        use diesel::prelude::*;
+
+        #body
+      }
+    }
+  }
+}
@@ -1,10 +1,342 @@
 #![allow(clippy::too_many_arguments)]
 
 use convert_case::{Case, Casing};
+use proc_macro2::Span;
 use quote::{format_ident, quote};
-use syn::Ident;
+use syn::{Ident, LitInt};
+
+use crate::{
+  attributes::{ExtraColumn, NameTypes, TestRuntime},
+  extra_columns::extra_columns_check,
+  KeyColumn, TokenStream2, VariantData,
+};
+
+// Counts the rows of a catalog query, trying the given queries in order until one of them is supported by the backend.
+// Each query is a list of sql fragments, which are joined with the text binds
+fn catalog_count(queries: &[&[&str]], binds: &[TokenStream2]) -> TokenStream2 {
+  let mut attempts = queries.iter().map(|fragments| {
+    let first = fragments[0];
+    let binds = binds.iter();
+    let rest = fragments[1..].iter();
 
-use crate::{attributes::NameTypes, TokenStream2, VariantData};
+    quote! {
+      diesel::dsl::sql::<diesel::sql_types::BigInt>(#first)
+        #(.bind::<diesel::sql_types::Text, _>(#binds).sql(#rest))*
+        .get_result::<i64>(conn)
+    }
+  });
+
+  let first = attempts.next().unwrap();
+
+  quote! {
+    #first #(.or_else(|_| #attempts))*
+  }
+}
+
+// Maps the error of a failed query on a table to a `SourceNotFound` if the table or one of the columns is missing, or to a `QueryFailed` otherwise.
+// The backend of the connection is not known, so the catalogs of postgres (in the schemas of the search path), mysql (in the current database) and sqlite are tried in order
+pub fn table_query_error(
+  table_name: &str,
+  columns: &[String],
+  source_type: &TokenStream2,
+  query_sql: &str,
+) -> TokenStream2 {
+  let table_count = catalog_count(
+    &[
+      &["SELECT COUNT(*) FROM information_schema.tables WHERE table_schema = ANY(current_schemas(false)) AND table_name = ", ""],
+      &["SELECT COUNT(*) FROM information_schema.tables WHERE table_schema = DATABASE() AND table_name = ", ""],
+      &["SELECT COUNT(*) FROM sqlite_master WHERE type IN ('table', 'view') AND name = ", ""],
+    ],
+    &[quote! { #table_name }],
+  );
+
+  let column_count = catalog_count(
+    &[
+      &["SELECT COUNT(*) FROM information_schema.columns WHERE table_schema = ANY(current_schemas(false)) AND table_name = ", " AND column_name = ", ""],
+      &["SELECT COUNT(*) FROM information_schema.columns WHERE table_schema = DATABASE() AND table_name = ", " AND column_name = ", ""],
+      &["SELECT COUNT(*) FROM pragma_table_info(", ") WHERE name = ", ""],
+    ],
+    &[quote! { #table_name }, quote! { column }],
+  );
+
+  quote! {
+    |e| {
+      // If a lookup itself fails, the original error is reported
+      let table_exists = #table_count.map_or(true, |count| count > 0);
+
+      let mut column_exists = |column: &str| #column_count.map_or(true, |count| count > 0);
+
+      if !table_exists {
+        diesel_enums::DbEnumError::source_not_found(
+          enum_name,
+          #source_type,
+          format!("the table `{}` does not exist", #table_name),
+        )
+      } else if let Some(column) = [#(#columns),*].into_iter().find(|column| !column_exists(column)) {
+        diesel_enums::DbEnumError::source_not_found(
+          enum_name,
+          #source_type,
+          format!("the column `{}.{}` does not exist", #table_name, column),
+        )
+      } else {
+        diesel_enums::DbEnumError::query_failed(enum_name, #source_type, #query_sql, e)
+      }
+    }
+  }
+}
+
+// With a fallback variant, the values that only exist in the database are not errors, since they can still be read, so they are returned as a warning instead
+fn fallback_warning(fallback: Option<&Ident>, source_type: &TokenStream2) -> TokenStream2 {
+  if let Some(fallback) = fallback {
+    let fallback_str = fallback.to_string();
+
+    quote! {
+      let unmapped_values = (!missing_variants.is_empty()).then(|| {
+        missing_variants.sort();
+
+        diesel_enums::UnmappedValues {
+          rust_enum: enum_name.to_string(),
+          db_source: #source_type,
+          fallback: #fallback_str.to_string(),
+          values: std::mem::take(&mut missing_variants),
+        }
+      });
+    }
+  } else {
+    quote! {
+      let unmapped_values: Option<diesel_enums::UnmappedValues> = None;
+    }
+  }
+}
+
+// Maps the aliases of the variants to their canonical names
+fn aliases_map(variants_data: &[VariantData]) -> TokenStream2 {
+  let (aliases, names): (Vec<&String>, Vec<&String>) = variants_data
+    .iter()
+    .flat_map(|data| data.aliases.iter().map(|alias| (alias, &data.db_name)))
+    .unzip();
+
+  quote! {
+    let aliases: HashMap<&'static str, &'static str> = HashMap::from([ #((#aliases, #names)),* ]);
+  }
+}
+
+// The ways in which the consistency checks can obtain the values of the database source, stored in `db_variants`
+struct VariantsSource {
+  // Loads the values from the database
+  from_db: TokenStream2,
+  // Loads the values from the `snapshot` variable
+  from_snapshot: TokenStream2,
+  // Converts the values into the variants of a snapshot
+  to_snapshot: TokenStream2,
+}
+
+// Wraps the body of the consistency check in the methods that run it, namely the test-only `check_consistency`, which uses the `conn` runner (or the snapshot, when there is no database available), and the public `check_consistency_with`, which is generated with `runtime_check` and registered for `check_all`
+fn consistency_checks<F>(
+  enum_name: &Ident,
+  enum_name_str: &str,
+  source_type: &TokenStream2,
+  conn_callback: Option<&TokenStream2>,
+  runtime_conn: Option<&TokenStream2>,
+  snapshot: Option<&str>,
+  test_runtime: &TestRuntime,
+  skip_test: bool,
+  variants_source: VariantsSource,
+  extra_columns_check: TokenStream2,
+  check_body: F,
+) -> TokenStream2
+where
+  F: Fn(&TokenStream2, &TokenStream2) -> TokenStream2,
+{
+  let VariantsSource {
+    from_db,
+    from_snapshot,
+    to_snapshot,
+  } = variants_source;
+
+  let runtime_check = runtime_conn.map(|runtime_conn| {
+    let runtime_body = check_body(&from_db, &extra_columns_check);
+
+    quote! {
+      impl #enum_name {
+        /// Checks if the enum is in sync with its database source, using the given connection.
+        ///
+        /// Unlike `check_consistency`, it is also available outside of tests, so that the mapping can be validated at runtime (i.e. when a service starts up).
+        pub fn check_consistency_with(conn: &mut #runtime_conn) -> diesel_enums::CheckResult {
+          use diesel::prelude::*;
+          use std::collections::{HashMap, HashSet};
+
+          #runtime_body
+        }
+      }
+
+      diesel_enums::inventory::submit! {
+        diesel_enums::RegisteredEnum {
+          rust_enum: #enum_name_str,
+          db_source: || #source_type,
+          check: |conn| conn.downcast_mut::<#runtime_conn>().map(#enum_name::check_consistency_with),
+        }
+      }
+    }
+  });
+
+  let test_mod = conn_callback.map(|conn_callback| {
+    let test_mod_name = format_ident!("__diesel_enum_test_{}", enum_name_str.to_case(Case::Snake));
+
+    let auto_test = if !skip_test {
+      let test_func_name = format_ident!("diesel_enum_test_{}", enum_name_str.to_case(Case::Snake));
+
+      // The runtimes without a test attribute of their own use `block_on` inside a regular test
+      let test_func = match test_runtime {
+        TestRuntime::Sync => quote! {
+          #[test]
+          fn #test_func_name() {
+            if let Some(unmapped_values) = #enum_name::check_consistency().unwrap() {
+              eprintln!("\n{unmapped_values}");
+            }
+          }
+        },
+        TestRuntime::Tokio => quote! {
+          #[tokio::test]
+          async fn #test_func_name() {
+            if let Some(unmapped_values) = #enum_name::check_consistency().await.unwrap() {
+              eprintln!("\n{unmapped_values}");
+            }
+          }
+        },
+        TestRuntime::TokioMultiThread => quote! {
+          #[tokio::test(flavor = "multi_thread")]
+          async fn #test_func_name() {
+            if let Some(unmapped_values) = #enum_name::check_consistency().await.unwrap() {
+              eprintln!("\n{unmapped_values}");
+            }
+          }
+        },
+        TestRuntime::AsyncStd => quote! {
+          #[test]
+          fn #test_func_name() {
+            if let Some(unmapped_values) = async_std::task::block_on(#enum_name::check_consistency()).unwrap() {
+              eprintln!("\n{unmapped_values}");
+            }
+          }
+        },
+        TestRuntime::Smol => quote! {
+          #[test]
+          fn #test_func_name() {
+            if let Some(unmapped_values) = smol::block_on(#enum_name::check_consistency()).unwrap() {
+              eprintln!("\n{unmapped_values}");
+            }
+          }
+        },
+      };
+
+      Some(test_func)
+    } else {
+      None
+    };
+
+    let (offline_method, offline_check, snapshot_update) = snapshot
+      .map(|path| {
+        let snapshot_path = quote! { concat!(env!("CARGO_MANIFEST_DIR"), "/", #path) };
+
+        // The extra columns are not part of the snapshot
+        let offline_body = check_body(
+          &quote! {
+            let snapshot_path = #snapshot_path;
+            let snapshot = diesel_enums::snapshots::read_snapshot(snapshot_path)?;
+
+            #from_snapshot
+          },
+          &quote! {
+            let value_mismatches: Vec<diesel_enums::ErrorKind> = Vec::new();
+          },
+        );
+
+        (
+          quote! {
+            /// Checks if the enum is in sync with the snapshot of its database source, without connecting to the database.
+            pub fn check_consistency_offline() -> diesel_enums::CheckResult {
+              let check = || -> diesel_enums::CheckResult { #offline_body };
+
+              check().map_err(|error| error.with_enum(#enum_name_str, #source_type))
+            }
+          },
+          quote! {
+            if diesel_enums::snapshots::is_offline() {
+              return Self::check_consistency_offline();
+            }
+          },
+          quote! {
+            if diesel_enums::snapshots::should_update() {
+              diesel_enums::snapshots::write_snapshot(
+                #snapshot_path,
+                &diesel_enums::snapshots::EnumSnapshot { variants: #to_snapshot },
+              )?;
+            }
+          },
+        )
+      })
+      .map_or((None, None, None), |(method, check, update)| {
+        (Some(method), Some(check), Some(update))
+      });
+
+    let (async_keyword, await_call) = if test_runtime.is_async() {
+      (quote! { async }, quote! { .await })
+    } else {
+      (TokenStream2::new(), TokenStream2::new())
+    };
+
+    let test_body = check_body(
+      &quote! {
+        #from_db
+
+        #snapshot_update
+      },
+      &extra_columns_check,
+    );
+
+    quote! {
+      #[cfg(test)]
+      mod #test_mod_name {
+        use super::*;
+        use diesel::prelude::*;
+        use std::collections::{HashMap, HashSet};
+
+        impl #enum_name {
+          #offline_method
+
+          #[track_caller]
+          pub #async_keyword fn check_consistency() -> diesel_enums::CheckResult
+          {
+            #offline_check
+
+            // The runners only accept callbacks returning `()`, so the warning is sent back through a channel
+            let (sender, receiver) = std::sync::mpsc::channel();
+
+            #conn_callback(move |conn| {
+              let mut check = || -> diesel_enums::CheckResult { #test_body };
+
+              let _ = sender.send(check()?);
+
+              Ok(())
+            })#await_call
+            .map_err(|error| error.with_enum(#enum_name_str, #source_type))?;
+
+            Ok(receiver.try_recv().ok().flatten())
+          }
+        }
+
+        #auto_test
+      }
+    }
+  });
+
+  quote! {
+    #runtime_check
+
+    #test_mod
+  }
+}
 
 pub fn test_with_id(
   enum_name: &Ident,
@@ -12,40 +344,119 @@ pub fn test_with_id(
   table_path: &TokenStream2,
   table_name: &str,
   column_name: &str,
-  id_rust_type: &Ident,
-  conn_callback: &TokenStream2,
+  key_column: &KeyColumn,
+  conn_callback: Option<&TokenStream2>,
+  runtime_conn: Option<&TokenStream2>,
+  snapshot: Option<&str>,
+  test_runtime: &TestRuntime,
   variants_data: &[VariantData],
   skip_test: bool,
-  is_double_mapping: bool,
+  fallback: Option<&Ident>,
+  extra_columns: &[ExtraColumn],
 ) -> TokenStream2 {
+  let aliases_map = aliases_map(variants_data);
+
   let column_name_ident = format_ident!("{column_name}");
 
-  let test_mod_name = format_ident!("__diesel_enum_test_{}", enum_name_str.to_case(Case::Snake));
+  let source_type = quote! { diesel_enums::DbEnumSource::Column { table: #table_name.to_string(), column: #column_name.to_string() } };
+  let fallback_warning = fallback_warning(fallback, &source_type);
+
+  let extra_columns_check = extra_columns_check(
+    table_path,
+    table_name,
+    &column_name_ident,
+    &source_type,
+    extra_columns,
+    variants_data,
+  );
+
+  let (key_type, load_type, key_select) = match key_column {
+    KeyColumn::Id(rust_type) => (
+      quote! { #rust_type },
+      quote! { #rust_type },
+      quote! { #table_path::id },
+    ),
+    KeyColumn::Code(code_column) => {
+      let code_column_ident = format_ident!("{code_column}");
+
+      (
+        quote! { &'static str },
+        quote! { String },
+        quote! { #table_path::#code_column_ident },
+      )
+    }
+  };
+
+  let (snapshot_key, snapshot_value) = match key_column {
+    KeyColumn::Id(_) => (format_ident!("id"), quote! { i128::from(*key) }),
+    KeyColumn::Code(_) => (format_ident!("code"), quote! { key.clone() }),
+  };
+
+  let (key_mismatches, key_comparison, key_report) = match key_column {
+    KeyColumn::Id(_) => (
+      quote! { let mut key_mismatches: Vec<diesel_enums::IdMismatch> = Vec::new(); },
+      quote! {
+        if key != variant_key {
+          key_mismatches.push(diesel_enums::IdMismatch {
+            variant: name,
+            expected: variant_key as i128,
+            found: key as i128,
+          });
+        }
+      },
+      quote! {
+        if !key_mismatches.is_empty() {
+          error.errors.push(diesel_enums::ErrorKind::IdMismatches(key_mismatches));
+        }
+      },
+    ),
+    KeyColumn::Code(code_column) => (
+      quote! { let mut key_mismatches: Vec<diesel_enums::ErrorKind> = Vec::new(); },
+      quote! {
+        if key != variant_key {
+          key_mismatches.push(diesel_enums::ErrorKind::ValueMismatch {
+            variant: name,
+            column: #code_column.to_string(),
+            expected: format!("{:?}", variant_key),
+            found: format!("{:?}", key),
+          });
+        }
+      },
+      quote! {
+        error.errors.extend(key_mismatches);
+      },
+    ),
+  };
 
   let variants_map = {
     let mut collection_tokens = TokenStream2::new();
 
     let variants_map_ident = format_ident!("map");
 
+    // The keys are written as literals, since the accessors of an enum with a fallback variant return an `Option`
     for variant in variants_data {
       let db_name = &variant.db_name;
-      let variant_ident = &variant.ident;
 
-      if is_double_mapping {
-        let enum_with_id_mapping = format_ident!("{enum_name}Id");
+      let key = match key_column {
+        KeyColumn::Id(rust_type) => {
+          let id = LitInt::new(&format!("{}{}", variant.id, rust_type), Span::call_site());
 
-        collection_tokens.extend(quote! {
-          #variants_map_ident.insert(#db_name, #enum_with_id_mapping::#variant_ident.into());
-        });
-      } else {
-        collection_tokens.extend(quote! {
-          #variants_map_ident.insert(#db_name, #enum_name::#variant_ident.into());
-        });
-      }
+          quote! { #id }
+        }
+        KeyColumn::Code(_) => {
+          let code = variant.code.as_deref().unwrap_or_default();
+
+          quote! { #code }
+        }
+      };
+
+      collection_tokens.extend(quote! {
+        #variants_map_ident.insert(#db_name, #key);
+      });
     }
 
     quote! {
-      let mut #variants_map_ident: HashMap<&'static str, #id_rust_type> = HashMap::new();
+      let mut #variants_map_ident: HashMap<&'static str, #key_type> = HashMap::new();
 
       #collection_tokens
 
@@ -53,92 +464,120 @@ pub fn test_with_id(
     }
   };
 
-  let auto_test = if !skip_test {
-    let test_func_name = format_ident!("diesel_enum_test_{}", enum_name_str.to_case(Case::Snake));
+  let key_column_name = match key_column {
+    KeyColumn::Id(_) => "id",
+    KeyColumn::Code(code_column) => code_column,
+  };
 
-    Some(quote! {
-      #[tokio::test]
-      async fn #test_func_name() {
-        #enum_name::check_consistency().await.unwrap();
-      }
-    })
-  } else {
-    None
+  let query_sql = format!("SELECT {key_column_name}, {column_name} FROM {table_name}");
+  let query_error = table_query_error(
+    table_name,
+    &[key_column_name.to_string(), column_name.to_string()],
+    &source_type,
+    &query_sql,
+  );
+
+  let variants_source = VariantsSource {
+    from_db: quote! {
+      let db_variants: Vec<(#load_type, String)> = #table_path::table
+        .select((#key_select, #table_path::#column_name_ident))
+        .load(conn)
+        .map_err(#query_error)?;
+    },
+    from_snapshot: quote! {
+      let db_variants: Vec<(#load_type, String)> = snapshot
+        .variants
+        .iter()
+        .map(|variant| Ok((variant.#snapshot_key(snapshot_path)?, variant.name.clone())))
+        .collect::<Result<_, diesel_enums::DbEnumError>>()?;
+    },
+    to_snapshot: quote! {
+      db_variants
+        .iter()
+        .map(|(key, name)| diesel_enums::snapshots::SnapshotVariant {
+          #snapshot_key: Some(#snapshot_value),
+          ..diesel_enums::snapshots::SnapshotVariant::new(name.clone())
+        })
+        .collect()
+    },
   };
 
-  quote! {
-    #[cfg(test)]
-    mod #test_mod_name {
-      use super::*;
-      use diesel::prelude::*;
-      use std::collections::HashMap;
+  let check_body = |load_variants: &TokenStream2, extra_columns_check: &TokenStream2| quote! {
+    let enum_name = #enum_name_str;
+    let table_name = #table_name;
+    let column_name = #column_name;
 
-      impl #enum_name {
-        #[track_caller]
-        pub async fn check_consistency() -> Result<(), diesel_enums::DbEnumError>
-        {
-          #conn_callback(|conn| {
-            let enum_name = #enum_name_str;
-            let table_name = #table_name;
-            let column_name = #column_name;
-
-            let mut rust_variants: HashMap<&'static str, #id_rust_type> = {
-              #variants_map
-            };
-
-            let db_variants: Vec<(#id_rust_type, String)> = #table_path::table
-              .select((#table_path::id, #table_path::#column_name_ident))
-              .load(conn)
-              .unwrap_or_else(|e| panic!("\n ❌ Failed to load the variants for the rust enum `{enum_name}` from the database column `{table_name}.{column_name}`: {e}"));
-
-            let mut missing_variants: Vec<String> = Vec::new();
-
-            let mut id_mismatches: Vec<(String, i64, i64)> = Vec::new();
-
-            for (id, name) in db_variants {
-              let variant_id = if let Some(variant) = rust_variants.remove(name.as_str()) {
-                variant
-              } else {
-                missing_variants.push(name);
-                continue;
-              };
-
-              if id != variant_id {
-                id_mismatches.push((name, id as i64, variant_id as i64));
-              }
-            }
+    let rust_keys: HashMap<&'static str, #key_type> = {
+      #variants_map
+    };
 
-            if !missing_variants.is_empty() || !rust_variants.is_empty() || !id_mismatches.is_empty() {
-              let mut error = diesel_enums::DbEnumError::new(enum_name.to_string(), diesel_enums::DbEnumSource::Column { table: table_name.to_string(), column: column_name.to_string() });
+    let mut rust_variants: HashSet<&'static str> = rust_keys.keys().copied().collect();
 
-              if !id_mismatches.is_empty() {
-                error.errors.push(diesel_enums::ErrorKind::IdMismatches(id_mismatches));
-              }
+    #aliases_map
 
-              if !missing_variants.is_empty() {
-                missing_variants.sort();
+    #load_variants
 
-                error.errors.push(diesel_enums::ErrorKind::MissingFromRustEnum(missing_variants));
-              }
+    let mut missing_variants: Vec<String> = Vec::new();
 
-              if !rust_variants.is_empty() {
-                let mut excess_variants: Vec<String> = rust_variants.into_iter().map(|(name, _)| name.to_string()).collect();
-                excess_variants.sort();
+    #key_mismatches
 
-                error.errors.push(diesel_enums::ErrorKind::MissingFromDb(excess_variants));
-              }
+    for (key, name) in db_variants {
+      let canonical_name = aliases.get(name.as_str()).copied().unwrap_or(name.as_str());
 
-              Err(error)
-            } else {
-              Ok(())
-            }
-          }).await
-        }
+      let variant_key = if let Some(variant) = rust_keys.get(canonical_name) {
+        rust_variants.remove(canonical_name);
+        *variant
+      } else {
+        missing_variants.push(name);
+        continue;
+      };
+
+      #key_comparison
+    }
+
+    #fallback_warning
+
+    #extra_columns_check
+
+    if !missing_variants.is_empty() || !rust_variants.is_empty() || !key_mismatches.is_empty() || !value_mismatches.is_empty() {
+      let mut error = diesel_enums::DbEnumError::new(enum_name.to_string(), #source_type);
+
+      #key_report
+
+      if !missing_variants.is_empty() {
+        missing_variants.sort();
+
+        error.errors.push(diesel_enums::ErrorKind::MissingFromRustEnum(missing_variants));
       }
 
-      #auto_test
+      if !rust_variants.is_empty() {
+        let mut excess_variants: Vec<String> = rust_variants.into_iter().map(|name| name.to_string()).collect();
+        excess_variants.sort();
+
+        error.errors.push(diesel_enums::ErrorKind::MissingFromDb(excess_variants));
+      }
+
+      error.errors.extend(value_mismatches);
+
+      Err(error)
+    } else {
+      Ok(unmapped_values)
     }
-  }
+  };
+
+  consistency_checks(
+    enum_name,
+    enum_name_str,
+    &source_type,
+    conn_callback,
+    runtime_conn,
+    snapshot,
+    test_runtime,
+    skip_test,
+    variants_source,
+    extra_columns_check,
+    check_body,
+  )
 }
 
 pub fn test_without_id(
@@ -148,115 +587,218 @@ pub fn test_without_id(
   table_name: &str,
   column_name: &str,
   db_type: &NameTypes,
-  conn_callback: &TokenStream2,
+  conn_callback: Option<&TokenStream2>,
+  runtime_conn: Option<&TokenStream2>,
+  snapshot: Option<&str>,
+  test_runtime: &TestRuntime,
   variants_data: &[VariantData],
   skip_test: bool,
+  check_order: bool,
+  fallback: Option<&Ident>,
+  extra_columns: &[ExtraColumn],
 ) -> TokenStream2 {
-  let (names_query, source_type) = if let NameTypes::Custom { name: db_enum_name } = db_type {
-    (
-      quote! {
-        #[derive(diesel::deserialize::QueryableByName)]
-        struct DbEnum {
-          #[diesel(sql_type = diesel::sql_types::Text)]
-          pub variant: String
-        }
+  let aliases_map = aliases_map(variants_data);
+
+  let (names_query, source_type) = match db_type {
+    NameTypes::Custom { name: db_enum_name } => {
+      let source_type = quote! { diesel_enums::DbEnumSource::CustomEnum(#db_enum_name.to_string()) };
+      // `to_regtype` returns null for a type that does not exist, so that it can be told apart from other failures
+      let query_sql = "SELECT enumlabel AS variant FROM pg_enum WHERE enumtypid = to_regtype($1) ORDER BY enumsortorder";
+
+      (
+        quote! {
+          #[derive(diesel::deserialize::QueryableByName)]
+          struct DbEnum {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            pub variant: String
+          }
+
+          let result: Vec<DbEnum> = diesel::sql_query(#query_sql)
+            .bind::<diesel::sql_types::Text, _>(#db_enum_name)
+            .load(conn)
+            .map_err(|e| diesel_enums::DbEnumError::query_failed(enum_name, #source_type, #query_sql, e))?;
+
+          if result.is_empty() {
+            return Err(diesel_enums::DbEnumError::source_not_found(
+              enum_name,
+              #source_type,
+              format!("the type `{}` does not exist or has no values", #db_enum_name),
+            ));
+          }
+
+          result.into_iter().map(|res| res.variant).collect()
+        },
+        source_type,
+      )
+    }
+    NameTypes::MysqlEnum => {
+      let source_type = quote! { diesel_enums::DbEnumSource::MysqlEnum { table: #table_name.to_string(), column: #column_name.to_string() } };
+      let query_sql = "SELECT COLUMN_TYPE AS column_type FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ? AND COLUMN_NAME = ?";
+
+      (
+        quote! {
+          #[derive(diesel::deserialize::QueryableByName)]
+          struct DbColumn {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            pub column_type: String
+          }
+
+          let result: DbColumn = diesel::sql_query(#query_sql)
+            .bind::<diesel::sql_types::Text, _>(#table_name)
+            .bind::<diesel::sql_types::Text, _>(#column_name)
+            .get_result(conn)
+            .optional()
+            .map_err(|e| diesel_enums::DbEnumError::query_failed(enum_name, #source_type, #query_sql, e))?
+            .ok_or_else(|| diesel_enums::DbEnumError::source_not_found(enum_name, #source_type, format!("the column `{}.{}` does not exist", #table_name, #column_name)))?;
+
+          diesel_enums::parse_mysql_enum(&result.column_type)
+            .ok_or_else(|| diesel_enums::DbEnumError::query_failed(enum_name, #source_type, #query_sql, format!("the column `{}.{}` is not an ENUM", #table_name, #column_name)))?
+        },
+        source_type,
+      )
+    }
+    NameTypes::Text => {
+      let column_name_ident = format_ident!("{column_name}");
+      let source_type = quote! { diesel_enums::DbEnumSource::Column { table: #table_name.to_string(), column: #column_name.to_string() } };
+      let query_sql = format!("SELECT {column_name} FROM {table_name}");
+      let query_error = table_query_error(table_name, &[column_name.to_string()], &source_type, &query_sql);
+
+      (
+        quote! {
+          #table_path::table
+            .select(#table_path::#column_name_ident)
+            .load(conn)
+            .map_err(#query_error)?
+        },
+        source_type,
+      )
+    }
+  };
 
-        let result: Vec<DbEnum> = diesel::sql_query(concat!(r#"SELECT unnest(enum_range(NULL::"#, #db_enum_name, ")) AS variant"))
-          .load(conn)
-          .unwrap_or_else(|e| panic!("\n ❌ Failed to load the variants for the rust enum `{enum_name}` from the database enum `{}`: {e}", #db_enum_name));
+  let fallback_warning = fallback_warning(fallback, &source_type);
 
-        result.into_iter().map(|res| res.variant).collect()
-      },
-      quote! { diesel_enums::DbEnumSource::CustomEnum(#db_enum_name.to_string()) },
-    )
+  let extra_columns_check = extra_columns_check(
+    table_path,
+    table_name,
+    &format_ident!("{column_name}"),
+    &source_type,
+    extra_columns,
+    variants_data,
+  );
+
+  let variant_db_names: Vec<&String> = variants_data.iter().map(|data| &data.db_name).collect();
+
+  let order_check = if check_order {
+    quote! {
+      // A variant can appear more than once if the database also contains its aliases
+      let mut seen_names: HashSet<&str> = HashSet::new();
+
+      let found: Vec<String> = db_variants
+        .iter()
+        .map(|variant| aliases.get(variant.as_str()).copied().unwrap_or(variant.as_str()))
+        .filter(|name| rust_order.contains(name) && seen_names.insert(name))
+        .map(|name| name.to_string())
+        .collect();
+
+      let expected: Vec<String> = rust_order
+        .iter()
+        .filter(|name| found.iter().any(|variant| variant == *name))
+        .map(|name| name.to_string())
+        .collect();
+
+      (expected != found).then_some(diesel_enums::ErrorKind::OrderMismatch { expected, found })
+    }
   } else {
-    let column_name_ident = format_ident!("{column_name}");
+    quote! { None }
+  };
 
-    (
-      quote! {
-        #table_path::table
-          .select(#table_path::#column_name_ident)
-          .load(conn)
-          .unwrap_or_else(|e| panic!("\n ❌ Failed to load the variants for the rust enum `{enum_name}` from the database column `{}.{}`: {e}", #table_name, #column_name))
-      },
-      quote! { diesel_enums::DbEnumSource::Column { table: #table_name.to_string(), column: #column_name.to_string() } },
-    )
+  let variants_source = VariantsSource {
+    from_db: quote! {
+      let db_variants: Vec<String> = {
+        #names_query
+      };
+    },
+    from_snapshot: quote! {
+      let db_variants: Vec<String> = snapshot.variants.into_iter().map(|variant| variant.name).collect();
+    },
+    to_snapshot: quote! {
+      db_variants
+        .iter()
+        .map(|name| diesel_enums::snapshots::SnapshotVariant::new(name.clone()))
+        .collect()
+    },
   };
 
-  let test_mod_name = format_ident!("__diesel_enum_test_{}", enum_name_str.to_case(Case::Snake));
+  let check_body = |load_variants: &TokenStream2, extra_columns_check: &TokenStream2| quote! {
+    let enum_name = #enum_name_str;
 
-  let variant_db_names = variants_data.iter().map(|data| &data.db_name);
+    let rust_order = [ #(#variant_db_names),* ];
 
-  let auto_test = if !skip_test {
-    let test_func_name = format_ident!("diesel_enum_test_{}", enum_name_str.to_case(Case::Snake));
+    let mut rust_variants = HashSet::from(rust_order);
 
-    Some(quote! {
-      #[tokio::test]
-      async fn #test_func_name() {
-        #enum_name::check_consistency().await.unwrap();
-      }
-    })
-  } else {
-    None
-  };
+    #aliases_map
 
-  quote! {
-    #[cfg(test)]
-    mod #test_mod_name {
-      use super::*;
-      use diesel::prelude::*;
-      use std::collections::HashSet;
+    #load_variants
 
-      impl #enum_name {
-        #[track_caller]
-        pub async fn check_consistency() -> Result<(), diesel_enums::DbEnumError>
-        {
-          #conn_callback(|conn| {
-            let enum_name = #enum_name_str;
+    let order_mismatch: Option<diesel_enums::ErrorKind> = {
+      #order_check
+    };
 
-            let mut rust_variants = HashSet::from({
-              [ #(#variant_db_names),* ]
-            });
+    let mut missing_variants: Vec<String> = Vec::new();
 
-            let db_variants: Vec<String> = {
-              #names_query
-            };
+    for variant in db_variants {
+      let canonical_name = aliases.get(variant.as_str()).copied().unwrap_or(variant.as_str());
 
-            let mut missing_variants: Vec<String> = Vec::new();
+      rust_variants.remove(canonical_name);
 
-            for variant in db_variants {
-              let was_present = rust_variants.remove(variant.as_str());
+      if !rust_order.contains(&canonical_name) {
+        missing_variants.push(variant);
+      }
+    }
 
-              if !was_present {
-                missing_variants.push(variant);
-              }
-            }
+    #fallback_warning
 
-            if !missing_variants.is_empty() || !rust_variants.is_empty() {
-              let mut error = diesel_enums::DbEnumError::new(enum_name.to_string(), #source_type);
+    #extra_columns_check
 
-              if !missing_variants.is_empty() {
-                missing_variants.sort();
+    if !missing_variants.is_empty() || !rust_variants.is_empty() || order_mismatch.is_some() || !value_mismatches.is_empty() {
+      let mut error = diesel_enums::DbEnumError::new(enum_name.to_string(), #source_type);
 
-                error.errors.push(diesel_enums::ErrorKind::MissingFromRustEnum(missing_variants));
-              }
+      if !missing_variants.is_empty() {
+        missing_variants.sort();
 
-              if !rust_variants.is_empty() {
-                let mut excess_variants: Vec<String> = rust_variants.into_iter().map(|name| name.to_string()).collect();
-                excess_variants.sort();
+        error.errors.push(diesel_enums::ErrorKind::MissingFromRustEnum(missing_variants));
+      }
 
-                error.errors.push(diesel_enums::ErrorKind::MissingFromDb(excess_variants));
-              }
+      if !rust_variants.is_empty() {
+        let mut excess_variants: Vec<String> = rust_variants.into_iter().map(|name| name.to_string()).collect();
+        excess_variants.sort();
 
-              Err(error)
-            } else {
-              Ok(())
-            }
-          }).await
-        }
+        error.errors.push(diesel_enums::ErrorKind::MissingFromDb(excess_variants));
+      }
+
+      if let Some(order_mismatch) = order_mismatch {
+        error.errors.push(order_mismatch);
       }
 
-      #auto_test
+      error.errors.extend(value_mismatches);
+
+      Err(error)
+    } else {
+      Ok(unmapped_values)
     }
-  }
+  };
+
+  consistency_checks(
+    enum_name,
+    enum_name_str,
+    &source_type,
+    conn_callback,
+    runtime_conn,
+    snapshot,
+    test_runtime,
+    skip_test,
+    variants_source,
+    extra_columns_check,
+    check_body,
+  )
 }